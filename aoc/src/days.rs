@@ -0,0 +1,56 @@
+//! The table of solvers for each day.
+
+use common::{Answer, Error, InputSource, Result, Solver};
+
+/// A single registered day.
+pub struct Day {
+    /// The day number, as given in the puzzle.
+    pub number: u32,
+
+    /// The directory (relative to the workspace) holding this day's usual
+    /// input.
+    pub dir: &'static str,
+
+    pub solver: &'static (dyn Solver + Sync),
+}
+
+/// All of the days that have solutions, in order.
+pub static DAYS: &[Day] = &[
+    Day { number: 1, dir: "day01", solver: &day01::Day01 },
+    Day { number: 2, dir: "day02a", solver: &Day02 },
+    Day { number: 3, dir: "day03", solver: &day03::Day03 },
+    Day { number: 4, dir: "day04", solver: &day04::Day04 },
+    Day { number: 5, dir: "day05", solver: &day05::Day05 },
+    Day { number: 6, dir: "day06", solver: &day06::Day06 },
+    Day { number: 7, dir: "day07", solver: &day07::Day07 },
+    Day { number: 8, dir: "day08", solver: &day08::Day08 },
+    Day { number: 9, dir: "day09", solver: &day09::Day09 },
+    Day { number: 10, dir: "day10", solver: &day10::Day10 },
+    Day { number: 11, dir: "day11", solver: &day11::Day11 },
+    Day { number: 12, dir: "day12", solver: &day12::Day12 },
+    Day { number: 13, dir: "day13", solver: &day13::Day13 },
+    Day { number: 14, dir: "day14", solver: &day14::Day14 },
+    Day { number: 16, dir: "day16", solver: &day16::Day16 },
+];
+
+/// Look up the given day.
+pub fn find(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
+
+/// Day 2 was written as two separate programs, one for each part.
+struct Day02;
+
+impl Solver for Day02 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let lines = day02a::get_input(input)?;
+        Ok(day02a::checksum(&lines).into())
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let lines = day02b::get_input(input)?;
+        day02b::solve(&lines)
+            .map(Answer::Text)
+            .ok_or_else(|| Error::solve("No pair of ids differs by one character"))
+    }
+}
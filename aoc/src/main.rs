@@ -0,0 +1,74 @@
+//! Runner for all of the days.
+//!
+//! Usage: `aoc run [<day> [<part> [<input>]]]`.  Without a part, both
+//! parts of the day are run, and without a day, every day is run.  The
+//! input can be given as a file name, or as "-" to read standard input.
+//! Otherwise, each day reads its usual input from its own directory, or
+//! from the directory named by `AOC_INPUTS` if that is set.
+
+use std::{
+    env,
+    path::Path,
+};
+use common::{Error, InputSource, Result};
+use crate::days::{Day, DAYS};
+
+mod days;
+
+fn main() {
+    common::run(try_main);
+}
+
+fn try_main() -> Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(|a| a.as_str()).collect();
+
+    match &args[..] {
+        ["run"] => {
+            for day in DAYS {
+                run(day, None, None)?;
+            }
+            Ok(())
+        }
+        ["run", day] => run(lookup(day)?, None, None),
+        ["run", day, part] => run(lookup(day)?, Some(parse_part(part)?), None),
+        ["run", day, part, input] => {
+            run(lookup(day)?, Some(parse_part(part)?), Some(input))
+        }
+        _ => Err(Error::usage("Usage: aoc run [<day> [<part> [<input>]]]")),
+    }
+}
+
+fn lookup(day: &str) -> Result<&'static Day> {
+    let number = day.parse().map_err(|_| Error::usage(format!("Invalid day: {:?}", day)))?;
+    days::find(number).ok_or_else(|| Error::usage(format!("No solution for day {}", number)))
+}
+
+fn parse_part(part: &str) -> Result<u32> {
+    match part {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(Error::usage(format!("Invalid part: {:?}", part))),
+    }
+}
+
+/// Run a given day, either just the given part, or both parts.
+fn run(day: &Day, part: Option<u32>, input: Option<&str>) -> Result<()> {
+    let input = match input {
+        Some(_) => InputSource::from_arg(input)?,
+        None => {
+            let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..").join(day.dir);
+            InputSource::Default(dir)
+        }
+    };
+
+    if part.is_none() || part == Some(1) {
+        println!("Day {}, part 1:", day.number);
+        println!("{}", day.solver.part1(&input)?);
+    }
+    if part.is_none() || part == Some(2) {
+        println!("Day {}, part 2:", day.number);
+        println!("{}", day.solver.part2(&input)?);
+    }
+    Ok(())
+}
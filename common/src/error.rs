@@ -0,0 +1,143 @@
+//! The error type shared by all of the days.
+
+use std::{
+    error,
+    fmt,
+    io,
+    path::Path,
+};
+use crate::parse::ParseError;
+
+/// Everything that can go wrong while solving a puzzle.
+pub enum Error {
+    /// Reading the input, or writing output, failed.  `context` says
+    /// what was being done.
+    Io {
+        context: String,
+        source: io::Error,
+    },
+
+    /// The input doesn't have the expected format.
+    Parse(ParseError),
+
+    /// The input was read, but the puzzle can't be solved with it.
+    Solve(String),
+
+    /// The program was run with the wrong arguments.
+    Usage(String),
+
+    /// One of the patterns used to read the input is invalid.
+    Regex(regex::Error),
+
+    /// An error from another library, such as the virtual machine.
+    Other(Box<dyn error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// A failure in the solver itself, rather than in reading the input.
+    pub fn solve<S: Into<String>>(message: S) -> Error {
+        Error::Solve(message.into())
+    }
+
+    /// The command line couldn't be understood.
+    pub fn usage<S: Into<String>>(message: S) -> Error {
+        Error::Usage(message.into())
+    }
+
+    /// An I/O error encountered while working with the given path.
+    pub fn io_path(path: &Path, source: io::Error) -> Error {
+        Error::Io {
+            context: format!("Unable to open {:?}", path),
+            source,
+        }
+    }
+
+    /// An I/O error encountered while writing output to the given path.
+    pub fn io_write(path: &Path, source: io::Error) -> Error {
+        Error::io(format!("Unable to write to {:?}", path), source)
+    }
+
+    /// Any other I/O error, with a description of what was being done.
+    /// Errors converted with `?` are taken to be from reading the input.
+    pub fn io<S: Into<String>>(context: S, source: io::Error) -> Error {
+        Error::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// Report the error on standard error, followed by each of its
+    /// causes.  Parse errors already start with "error:".
+    pub fn report(&self) {
+        match self {
+            Error::Parse(err) => eprintln!("{}", err),
+            _ => eprintln!("error: {}", self),
+        }
+        let mut source = error::Error::source(self);
+        while let Some(err) = source {
+            eprintln!("  caused by: {}", err);
+            source = err.source();
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io { context, .. } => write!(f, "{}", context),
+            Error::Parse(err) => write!(f, "{}", err),
+            Error::Solve(message) => write!(f, "{}", message),
+            Error::Usage(message) => write!(f, "{}", message),
+            Error::Regex(_) => write!(f, "Invalid input pattern"),
+            Error::Other(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+/// Show the message followed by each of its causes, so that failing tests
+/// are readable.
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)?;
+        let mut source = error::Error::source(self);
+        while let Some(err) = source {
+            write!(f, "\n  caused by: {}", err)?;
+            source = err.source();
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Regex(err) => Some(err),
+            // This error's message has already been shown, so continue
+            // with what caused it.
+            Error::Other(err) => err.source(),
+            Error::Parse(_) | Error::Solve(_) | Error::Usage(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io {
+            context: "Error reading input".to_string(),
+            source: err,
+        }
+    }
+}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Error {
+        Error::Parse(err)
+    }
+}
+
+impl From<regex::Error> for Error {
+    fn from(err: regex::Error) -> Error {
+        Error::Regex(err)
+    }
+}
@@ -0,0 +1,394 @@
+//! Two dimensional grids.
+//!
+//! Many of the puzzles take place on a 2D plane.  Positions are given as a
+//! `Point`, with x increasing to the right, and y increasing downward, the
+//! way the puzzle text is laid out.  A `Grid` holds a value for every
+//! point within some `Bounds`, and a `SparseGrid` holds values for only
+//! some of the points, without any fixed bounds.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    collections::{btree_map, BTreeMap},
+    iter::FromIterator,
+    ops::{Add, Index, IndexMut, Sub},
+};
+use crate::{
+    Answer,
+    parse::{Line, ParseError},
+};
+
+/// A position on the plane.  It is serialized as an object with `x` and
+/// `y` fields.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const UP: Point = Point { x: 0, y: -1 };
+    pub const DOWN: Point = Point { x: 0, y: 1 };
+    pub const LEFT: Point = Point { x: -1, y: 0 };
+    pub const RIGHT: Point = Point { x: 1, y: 0 };
+
+    pub fn new(x: i64, y: i64) -> Point {
+        Point { x, y }
+    }
+
+    /// The "taxicab" distance between two points.
+    pub fn manhattan(self, other: Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// The four points directly above, left, right and below this one.
+    pub fn neighbors4(self) -> [Point; 4] {
+        [self + Point::UP, self + Point::LEFT, self + Point::RIGHT, self + Point::DOWN]
+    }
+
+    /// The eight points surrounding this one, including the diagonals, in
+    /// reading order.
+    pub fn neighbors8(self) -> [Point; 8] {
+        let Point { x, y } = self;
+        [
+            Point::new(x - 1, y - 1), Point::new(x, y - 1), Point::new(x + 1, y - 1),
+            Point::new(x - 1, y), Point::new(x + 1, y),
+            Point::new(x - 1, y + 1), Point::new(x, y + 1), Point::new(x + 1, y + 1),
+        ]
+    }
+}
+
+/// Points are ordered in reading order: top to bottom, and then left to
+/// right.
+impl Ord for Point {
+    fn cmp(&self, other: &Point) -> Ordering {
+        (self.y, self.x).cmp(&(other.y, other.x))
+    }
+}
+
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Point) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl From<Point> for Answer {
+    fn from(p: Point) -> Answer {
+        Answer::Coord(p.x, p.y)
+    }
+}
+
+/// A rectangular region, including both corners.  A region with `max` to
+/// the left of, or above `min` is empty.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn new(min: Point, max: Point) -> Bounds {
+        Bounds { min, max }
+    }
+
+    /// The bounds of a region of the given size, with its top left corner
+    /// at the origin.
+    pub fn with_size(width: usize, height: usize) -> Bounds {
+        Bounds::new(Point::new(0, 0), Point::new(width as i64 - 1, height as i64 - 1))
+    }
+
+    /// The smallest bounds holding all of the given points, or None if
+    /// there aren't any.
+    pub fn from_points<I: IntoIterator<Item = Point>>(points: I) -> Option<Bounds> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        let mut bounds = Bounds::new(first, first);
+        for p in points {
+            bounds.include(p);
+        }
+        Some(bounds)
+    }
+
+    /// Grow these bounds, if necessary, to hold the given point.
+    pub fn include(&mut self, p: Point) {
+        self.min.x = self.min.x.min(p.x);
+        self.min.y = self.min.y.min(p.y);
+        self.max.x = self.max.x.max(p.x);
+        self.max.y = self.max.y.max(p.y);
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.x - self.min.x + 1).max(0) as usize
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.y - self.min.y + 1).max(0) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width() == 0 || self.height() == 0
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// Is this point on the outer boundary of the region?
+    pub fn on_edge(&self, p: Point) -> bool {
+        self.contains(p) &&
+            (p.x == self.min.x || p.x == self.max.x || p.y == self.min.y || p.y == self.max.y)
+    }
+
+    /// All of the points in the region, in reading order.
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let Bounds { min, max } = *self;
+        (min.y ..= max.y).flat_map(move |y| (min.x ..= max.x).map(move |x| Point::new(x, y)))
+    }
+}
+
+/// A value for every point within some bounds.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    bounds: Bounds,
+
+    /// The cells, in reading order.
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// A grid covering the given bounds, with every cell set to `fill`.
+    pub fn new(bounds: Bounds, fill: T) -> Grid<T>
+        where T: Clone
+    {
+        Grid {
+            bounds,
+            cells: vec![fill; bounds.width() * bounds.height()],
+        }
+    }
+
+    /// A grid covering the given bounds, with each cell computed from its
+    /// position.
+    pub fn from_fn<F: FnMut(Point) -> T>(bounds: Bounds, f: F) -> Grid<T> {
+        Grid {
+            bounds,
+            cells: bounds.points().map(f).collect(),
+        }
+    }
+
+    /// Build a grid from lines of text, with the first character of the
+    /// first line at the origin.  Each character is converted by `decode`,
+    /// and a character it returns None for is reported as an error.
+    /// Short lines are padded out with `fill`.
+    pub fn from_lines<F>(lines: &[Line], fill: T, mut decode: F) -> Result<Grid<T>, ParseError>
+        where T: Clone,
+              F: FnMut(Point, char) -> Option<T>,
+    {
+        let width = lines.iter().map(|line| line.text.chars().count()).max().unwrap_or(0);
+        let mut grid = Grid::new(Bounds::with_size(width, lines.len()), fill);
+
+        for (y, line) in lines.iter().enumerate() {
+            for (x, (offset, ch)) in line.text.char_indices().enumerate() {
+                let p = Point::new(x as i64, y as i64);
+                match decode(p, ch) {
+                    Some(cell) => grid[p] = cell,
+                    None => return Err(line.error(offset, format!("unexpected character {:?}", ch))),
+                }
+            }
+        }
+
+        Ok(grid)
+    }
+
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    pub fn width(&self) -> usize {
+        self.bounds.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds.height()
+    }
+
+    fn index_of(&self, p: Point) -> Option<usize> {
+        if self.bounds.contains(p) {
+            let x = (p.x - self.bounds.min.x) as usize;
+            let y = (p.y - self.bounds.min.y) as usize;
+            Some(y * self.width() + x)
+        } else {
+            None
+        }
+    }
+
+    /// The cell at the given point, or None if it is outside of the grid.
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.index_of(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        match self.index_of(p) {
+            Some(i) => Some(&mut self.cells[i]),
+            None => None,
+        }
+    }
+
+    /// The cells of a single row, or None if the row is outside of the
+    /// grid.
+    pub fn row(&self, y: i64) -> Option<&[T]> {
+        if y < self.bounds.min.y || y > self.bounds.max.y {
+            return None;
+        }
+        let start = (y - self.bounds.min.y) as usize * self.width();
+        Some(&self.cells[start .. start + self.width()])
+    }
+
+    /// All of the cells, with their positions, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.bounds.points().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Point, &mut T)> {
+        self.bounds.points().zip(self.cells.iter_mut())
+    }
+
+    /// The neighbors of a point, above, left, right and below, that are
+    /// within the grid.
+    pub fn neighbors4(&self, p: Point) -> impl Iterator<Item = Point> {
+        let bounds = self.bounds;
+        p.neighbors4().to_vec().into_iter().filter(move |&n| bounds.contains(n))
+    }
+
+    /// The neighbors of a point, including diagonals, that are within the
+    /// grid.
+    pub fn neighbors8(&self, p: Point) -> impl Iterator<Item = Point> {
+        let bounds = self.bounds;
+        p.neighbors8().to_vec().into_iter().filter(move |&n| bounds.contains(n))
+    }
+
+    /// Render the grid as rows of text, with `f` giving the character for
+    /// each cell.
+    pub fn render<F: FnMut(Point, &T) -> char>(&self, mut f: F) -> Vec<String> {
+        self.cells.chunks(self.width().max(1)).zip(self.bounds.min.y ..).map(|(row, y)| {
+            row.iter().zip(self.bounds.min.x ..).map(|(cell, x)| f(Point::new(x, y), cell)).collect()
+        }).collect()
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point) -> &T {
+        match self.index_of(p) {
+            Some(i) => &self.cells[i],
+            None => panic!("Point {:?} outside of grid {:?}", p, self.bounds),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, p: Point) -> &mut T {
+        match self.index_of(p) {
+            Some(i) => &mut self.cells[i],
+            None => panic!("Point {:?} outside of grid {:?}", p, self.bounds),
+        }
+    }
+}
+
+/// Values for some of the points on an unbounded plane.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: BTreeMap<Point, T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid { cells: BTreeMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn get(&self, p: Point) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Point) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains_key(&p)
+    }
+
+    /// Set the value at a point, returning the value that was there.
+    pub fn insert(&mut self, p: Point, value: T) -> Option<T> {
+        self.cells.insert(p, value)
+    }
+
+    pub fn remove(&mut self, p: Point) -> Option<T> {
+        self.cells.remove(&p)
+    }
+
+    pub fn entry(&mut self, p: Point) -> btree_map::Entry<'_, Point, T> {
+        self.cells.entry(p)
+    }
+
+    /// The points that have values, with those values, in reading order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(&p, v)| (p, v))
+    }
+
+    /// The smallest bounds holding every point with a value.
+    pub fn bounds(&self) -> Option<Bounds> {
+        Bounds::from_points(self.cells.keys().cloned())
+    }
+
+    /// Render the area covered by the grid as rows of text, with `f`
+    /// giving the character for each point, whether or not it has a value.
+    pub fn render<F: FnMut(Point, Option<&T>) -> char>(&self, mut f: F) -> Vec<String> {
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return vec![],
+        };
+        (bounds.min.y ..= bounds.max.y).map(|y| {
+            (bounds.min.x ..= bounds.max.x).map(|x| {
+                let p = Point::new(x, y);
+                f(p, self.get(p))
+            }).collect()
+        }).collect()
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> SparseGrid<T> {
+        SparseGrid::new()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> SparseGrid<T> {
+        SparseGrid { cells: iter.into_iter().collect() }
+    }
+}
@@ -0,0 +1,105 @@
+//! Locating and reading the puzzle input.
+
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, Read},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+use crate::{
+    Error,
+    Result,
+    parse::Line,
+};
+
+/// If set, this environment variable names a directory holding the inputs
+/// for every day, as `<day>.txt`, for example `day04.txt`.
+pub const INPUTS_VAR: &str = "AOC_INPUTS";
+
+/// Where a day's input should come from.
+#[derive(Clone, Debug)]
+pub enum InputSource {
+    /// The day's usual input.  This is looked for in the directory named
+    /// by `AOC_INPUTS`, if that is set, and otherwise under its usual name
+    /// in the given directory.
+    Default(PathBuf),
+
+    /// A specific file.
+    File(PathBuf),
+
+    /// The input text itself.  Standard input is read into one of these,
+    /// so that it can be read again for the second part.
+    Text(String),
+}
+
+impl InputSource {
+    /// Decode an input argument.  No argument gives the default input
+    /// relative to the current directory, and "-" reads standard input.
+    pub fn from_arg(arg: Option<&str>) -> Result<InputSource> {
+        match arg {
+            None => Ok(InputSource::Default(PathBuf::from("."))),
+            Some("-") => {
+                let mut text = String::new();
+                io::stdin().read_to_string(&mut text)?;
+                Ok(InputSource::Text(text))
+            }
+            Some(name) => Ok(InputSource::File(PathBuf::from(name))),
+        }
+    }
+
+    /// Decode the input from the first command line argument.
+    pub fn from_args() -> Result<InputSource> {
+        let args: Vec<String> = env::args().skip(1).collect();
+        match args.len() {
+            0 | 1 => InputSource::from_arg(args.first().map(|a| a.as_str())),
+            _ => Err(Error::usage("Usage: [<input> | -]")),
+        }
+    }
+
+    /// Open the input for reading.  `day` is used to find the input in the
+    /// inputs directory, and `name` is the day's usual input file name.
+    pub fn open(&self, day: &str, name: &str) -> Result<Box<dyn BufRead>> {
+        match self {
+            InputSource::Default(dir) => open_file(&default_path(dir, day, name)),
+            InputSource::File(path) => open_file(path),
+            InputSource::Text(text) => Ok(Box::new(Cursor::new(text.clone().into_bytes()))),
+        }
+    }
+
+    /// Read all of the lines of the input, each tagged with where it came
+    /// from, so that parse errors can refer back to them.
+    pub fn lines(&self, day: &str, name: &str) -> Result<Vec<Line>> {
+        let file: Rc<str> = self.display_name(day, name).into();
+
+        self.open(day, name)?.lines().enumerate().map(|(i, text)| {
+            Ok(Line {
+                file: file.clone(),
+                num: i + 1,
+                text: text?,
+            })
+        }).collect()
+    }
+
+    /// The name to use for the input in messages.
+    pub fn display_name(&self, day: &str, name: &str) -> String {
+        match self {
+            InputSource::Default(dir) => default_path(dir, day, name).display().to_string(),
+            InputSource::File(path) => path.display().to_string(),
+            InputSource::Text(_) => "<stdin>".to_string(),
+        }
+    }
+}
+
+/// Where the day's usual input is found.
+fn default_path(dir: &Path, day: &str, name: &str) -> PathBuf {
+    match env::var_os(INPUTS_VAR) {
+        Some(inputs) => Path::new(&inputs).join(format!("{}.txt", day)),
+        None => dir.join(name),
+    }
+}
+
+fn open_file(path: &Path) -> Result<Box<dyn BufRead>> {
+    let f = File::open(path).map_err(|e| Error::io_path(path, e))?;
+    Ok(Box::new(BufReader::new(f)))
+}
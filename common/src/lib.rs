@@ -0,0 +1,106 @@
+//! Support shared between the solutions for each day.
+
+use std::{
+    fmt,
+    process,
+    result,
+};
+
+pub use crate::error::Error;
+pub use crate::grid::{Bounds, Grid, Point, SparseGrid};
+pub use crate::input::InputSource;
+pub use crate::parse::{eof_error, Line, ParseError};
+
+mod error;
+mod grid;
+mod input;
+mod parse;
+
+pub type Result<T> = result::Result<T, Error>;
+
+/// Run a program's real main function, reporting any error it returns,
+/// and exiting with a failure status.  Returning the error from `main`
+/// would show it with `Debug`, after a prefix of its own.
+pub fn run<F: FnOnce() -> Result<()>>(main: F) {
+    if let Err(err) = main() {
+        err.report();
+        process::exit(1);
+    }
+}
+
+/// Each day's puzzle is solved by something implementing this trait.  The
+/// `aoc` runner keeps a table of these, and dispatches to them by day and
+/// part number.
+pub trait Solver {
+    /// Solve the first part of the puzzle, reading from the given input.
+    fn part1(&self, input: &InputSource) -> Result<Answer>;
+
+    /// Solve the second part of the puzzle, reading from the given input.
+    fn part2(&self, input: &InputSource) -> Result<Answer>;
+}
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    Coord(i64, i64),
+
+    /// A picture, that has to be read by eye.  Each string is a row.
+    Grid(Vec<String>),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Coord(x, y) => write!(f, "{},{}", x, y),
+            Answer::Grid(rows) => {
+                for (i, row) in rows.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", row)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Answer {
+        Answer::Int(n)
+    }
+}
+
+impl From<i32> for Answer {
+    fn from(n: i32) -> Answer {
+        Answer::Int(n as i64)
+    }
+}
+
+impl From<u32> for Answer {
+    fn from(n: u32) -> Answer {
+        Answer::Int(n as i64)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(n: u64) -> Answer {
+        Answer::Int(n as i64)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::Int(n as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Answer {
+        Answer::Text(text)
+    }
+}
@@ -0,0 +1,148 @@
+//! Errors from parsing the puzzle input, reported with enough context to
+//! find the problem.
+
+use regex::Captures;
+use std::{
+    error,
+    fmt,
+    rc::Rc,
+    str::FromStr,
+};
+
+/// A problem found while parsing the input.  This is displayed in the
+/// style of a compiler diagnostic:
+///
+/// ```text
+/// error: invalid number
+///   --> shift.txt:12:7
+///    |
+/// 12 | [1518-1x-05 00:03] falls asleep
+///    |       ^
+/// ```
+///
+/// For inputs like maps, where one line doesn't say much on its own, the
+/// lines around it can be shown too.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The name of the input file.
+    pub file: String,
+
+    /// The line the error is on, counting from 1.
+    pub line: usize,
+
+    /// The column the error is at, counting from 1.
+    pub column: usize,
+
+    /// The full text of the offending line.
+    pub text: String,
+
+    pub message: String,
+
+    /// Lines around the offending one, with their numbers, shown for
+    /// context.  This is usually empty.
+    pub context: Vec<(usize, String)>,
+}
+
+impl ParseError {
+    pub fn new<S: Into<String>>(file: &str, line: usize, column: usize, text: &str,
+                                message: S) -> ParseError
+    {
+        ParseError {
+            file: file.to_string(),
+            line,
+            column,
+            text: text.to_string(),
+            message: message.into(),
+            context: vec![],
+        }
+    }
+
+    /// Show the given lines, with their numbers, around the offending one.
+    pub fn with_context(mut self, mut context: Vec<(usize, String)>) -> ParseError {
+        context.sort();
+        self.context = context;
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let last = self.context.iter().map(|&(num, _)| num).fold(self.line, usize::max);
+        let width = last.to_string().len();
+        let pad = " ".repeat(width);
+        // Columns count characters, so the marker can be placed under the
+        // right one.
+        let mark = " ".repeat(self.column.saturating_sub(1));
+        let (before, after): (Vec<_>, Vec<_>) = self.context.iter().partition(|&&(num, _)| num < self.line);
+
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(f, "{}--> {}:{}:{}", pad, self.file, self.line, self.column)?;
+        writeln!(f, "{} |", pad)?;
+        for (num, text) in before {
+            writeln!(f, "{:>w$} | {}", num, text, w = width)?;
+        }
+        writeln!(f, "{:>w$} | {}", self.line, self.text, w = width)?;
+        write!(f, "{} | {}^", pad, mark)?;
+        for (num, text) in after {
+            write!(f, "\n{:>w$} | {}", num, text, w = width)?;
+        }
+        Ok(())
+    }
+}
+
+// Failing tests print errors with Debug, so make that just as readable.
+impl fmt::Debug for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// A single line of input, which remembers where it came from, so that
+/// errors can point back at it.
+#[derive(Clone, Debug)]
+pub struct Line {
+    pub file: Rc<str>,
+
+    /// The line number, counting from 1.
+    pub num: usize,
+
+    pub text: String,
+}
+
+impl Line {
+    /// Construct an error at the given byte offset into this line.
+    pub fn error<S: Into<String>>(&self, offset: usize, message: S) -> ParseError {
+        let column = self.text[..offset.min(self.text.len())].chars().count() + 1;
+        ParseError::new(&self.file, self.num, column, &self.text, message)
+    }
+
+    /// Construct an error that applies to the whole line.
+    pub fn error_line<S: Into<String>>(&self, message: S) -> ParseError {
+        self.error(0, message)
+    }
+
+    /// Parse a field of this line, that starts at the given byte offset.
+    pub fn parse_at<T>(&self, offset: usize, field: &str) -> Result<T, ParseError>
+        where T: FromStr,
+              T::Err: fmt::Display,
+    {
+        field.parse().map_err(|e| self.error(offset, format!("invalid value {:?}: {}", field, e)))
+    }
+
+    /// Parse the given group of a regex match against this line.
+    pub fn parse_cap<T>(&self, cap: &Captures, group: usize) -> Result<T, ParseError>
+        where T: FromStr,
+              T::Err: fmt::Display,
+    {
+        let m = cap.get(group).expect("regex group did not participate in match");
+        self.parse_at(m.start(), m.as_str())
+    }
+}
+
+/// Construct an error for input that ended too soon.  `lines` is the
+/// number of lines that were read.
+pub fn eof_error<S: Into<String>>(file: &str, lines: usize, message: S) -> ParseError {
+    ParseError::new(file, lines + 1, 1, "", message)
+}
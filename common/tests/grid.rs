@@ -0,0 +1,146 @@
+use common::{Bounds, Grid, InputSource, Line, Point, SparseGrid};
+
+fn lines(text: &str) -> Vec<Line> {
+    InputSource::Text(text.to_string()).lines("test", "test.txt").unwrap()
+}
+
+/// Decode walls and floor, and nothing else.
+fn decode(_: Point, ch: char) -> Option<bool> {
+    match ch {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
+    }
+}
+
+fn render(grid: &Grid<bool>) -> Vec<String> {
+    grid.render(|_, &wall| if wall { '#' } else { '.' })
+}
+
+#[test]
+fn from_lines() {
+    let grid = Grid::from_lines(&lines("#.#\n#\n\n.##.\n"), true, decode).unwrap();
+    assert_eq!(grid.bounds(), Bounds::with_size(4, 4));
+    assert_eq!((grid.width(), grid.height()), (4, 4));
+
+    // Short lines, and empty ones, are padded with the fill value.
+    assert_eq!(render(&grid), vec!["#.##", "####", "####", ".##."]);
+    assert!(!grid[Point::new(1, 0)]);
+    assert!(grid[Point::new(3, 0)]);
+
+    let grid = Grid::from_lines(&lines(""), false, decode).unwrap();
+    assert!(grid.bounds().is_empty());
+    assert_eq!(render(&grid), Vec::<String>::new());
+}
+
+#[test]
+fn from_lines_errors() {
+    let cases = [
+        ("#.#\n#x.\n", 2, 2, "unexpected character 'x'"),
+        ("#.#\n...\n..# \n", 3, 4, "unexpected character ' '"),
+        // Columns count characters, not bytes.
+        ("é#\n", 1, 1, "unexpected character 'é'"),
+        ("#é\n", 1, 2, "unexpected character 'é'"),
+    ];
+    for &(text, line, column, message) in &cases {
+        let err = Grid::from_lines(&lines(text), false, decode).unwrap_err();
+        assert_eq!((err.line, err.column, err.message.as_str()), (line, column, message), "{:?}", text);
+    }
+}
+
+#[test]
+fn neighbors() {
+    let grid = Grid::new(Bounds::new(Point::new(1, 1), Point::new(3, 3)), 0);
+    let n4 = |x, y| grid.neighbors4(Point::new(x, y)).collect::<Vec<_>>();
+    let n8 = |x, y| grid.neighbors8(Point::new(x, y)).collect::<Vec<_>>();
+    let points = |ps: &[(i64, i64)]| ps.iter().map(|&(x, y)| Point::new(x, y)).collect::<Vec<_>>();
+
+    // Corners.
+    assert_eq!(n4(1, 1), points(&[(2, 1), (1, 2)]));
+    assert_eq!(n4(3, 3), points(&[(3, 2), (2, 3)]));
+    assert_eq!(n8(1, 1), points(&[(2, 1), (1, 2), (2, 2)]));
+    assert_eq!(n8(3, 1), points(&[(2, 1), (2, 2), (3, 2)]));
+    assert_eq!(n8(3, 3), points(&[(2, 2), (3, 2), (2, 3)]));
+
+    // Edges.
+    assert_eq!(n4(2, 1), points(&[(1, 1), (3, 1), (2, 2)]));
+    assert_eq!(n4(1, 2), points(&[(1, 1), (2, 2), (1, 3)]));
+    assert_eq!(n8(2, 3), points(&[(1, 2), (2, 2), (3, 2), (1, 3), (3, 3)]));
+
+    // The middle has them all, the same as the point's own.
+    assert_eq!(n4(2, 2), Point::new(2, 2).neighbors4().to_vec());
+    assert_eq!(n8(2, 2), Point::new(2, 2).neighbors8().to_vec());
+
+    // A point outside is only next to the cells that touch it.
+    assert_eq!(n8(0, 0), points(&[(1, 1)]));
+    assert_eq!(n4(0, 0), points(&[]));
+}
+
+#[test]
+fn rows_and_render() {
+    let bounds = Bounds::new(Point::new(-1, 5), Point::new(1, 6));
+    let grid = Grid::from_fn(bounds, |p| p.x * 10 + p.y);
+    assert_eq!(grid.row(5), Some(&[-5, 5, 15][..]));
+    assert_eq!(grid.row(6), Some(&[-4, 6, 16][..]));
+    assert_eq!(grid.row(4), None);
+    assert_eq!(grid.row(7), None);
+    assert_eq!(grid.get(Point::new(2, 5)), None);
+
+    // The render function is given each cell's real position.
+    let rendered = grid.render(|p, &v| {
+        assert_eq!(v, p.x * 10 + p.y);
+        if p.x == 0 { '|' } else { '.' }
+    });
+    assert_eq!(rendered, vec![".|.", ".|."]);
+}
+
+#[test]
+fn empty_bounds() {
+    let bounds = Bounds::new(Point::new(0, 0), Point::new(-1, 3));
+    assert!(bounds.is_empty());
+    assert_eq!((bounds.width(), bounds.height()), (0, 4));
+    assert_eq!(bounds.points().count(), 0);
+    assert!(!bounds.contains(Point::new(0, 0)));
+
+    let bounds = Bounds::with_size(0, 0);
+    assert!(bounds.is_empty());
+    let grid = Grid::new(bounds, 'x');
+    assert_eq!(grid.iter().count(), 0);
+    assert_eq!(grid.row(0), None);
+    assert_eq!(grid.render(|_, &c| c), Vec::<String>::new());
+
+    assert_eq!(Bounds::from_points(vec![]), None);
+    assert!(!Bounds::with_size(1, 1).is_empty());
+}
+
+#[test]
+fn sparse() {
+    let mut grid: SparseGrid<char> = SparseGrid::new();
+    assert_eq!(grid.bounds(), None);
+    assert_eq!(grid.render(|_, _| '.'), Vec::<String>::new());
+
+    grid.insert(Point::new(2, -1), 'a');
+    assert_eq!(grid.bounds(), Some(Bounds::new(Point::new(2, -1), Point::new(2, -1))));
+    assert_eq!(grid.render(|_, v| v.cloned().unwrap_or('.')), vec!["a"]);
+
+    grid.insert(Point::new(-1, 1), 'b');
+    assert_eq!(grid.insert(Point::new(0, 0), 'c'), None);
+    assert_eq!(grid.insert(Point::new(0, 0), 'd'), Some('c'));
+    assert_eq!(grid.len(), 3);
+    assert_eq!(grid.bounds(), Some(Bounds::new(Point::new(-1, -1), Point::new(2, 1))));
+    assert_eq!(grid.render(|_, v| v.cloned().unwrap_or('.')), vec![
+        "...a",
+        ".d..",
+        "b...",
+    ]);
+
+    // Removing a point at the edge shrinks the bounds.
+    assert_eq!(grid.remove(Point::new(2, -1)), Some('a'));
+    assert_eq!(grid.bounds(), Some(Bounds::new(Point::new(-1, 0), Point::new(0, 1))));
+    assert_eq!(grid.render(|_, v| v.cloned().unwrap_or('.')), vec![".d", "b."]);
+
+    // Iteration is in reading order.
+    let grid: SparseGrid<i32> = vec![(Point::new(5, 2), 1), (Point::new(-5, 2), 2), (Point::new(9, 0), 3)]
+        .into_iter().collect();
+    assert_eq!(grid.iter().map(|(_, &v)| v).collect::<Vec<_>>(), vec![3, 2, 1]);
+}
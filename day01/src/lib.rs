@@ -0,0 +1,84 @@
+use std::{
+    collections::HashSet,
+    result,
+};
+use common::{Answer, Error, InputSource, Line, ParseError, Result, Solver};
+
+pub struct Day01;
+
+impl Solver for Day01 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let nums = get_input(input)?;
+        Ok(nums.iter().map(|&n| n as i64).sum::<i64>().into())
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let nums = get_input(input)?;
+        let (result, _) = first_repeat(&nums)?;
+        Ok(result.into())
+    }
+}
+
+/// Find the first cumulative frequency that is reached twice, cycling
+/// through the changes as many times as needed.  The starting frequency of
+/// 0 counts as reached.  Returns that frequency, and the number of
+/// iterations it took to find it.
+pub fn first_repeat(nums: &[i32]) -> Result<(i64, usize)> {
+    // With no changes, the loop below would never get anywhere.
+    if nums.is_empty() {
+        return Err(Error::solve("No frequency changes given"));
+    }
+
+    // Each pass shifts every frequency by the same drift, so a frequency
+    // can only come back once the drift has covered the spread of a single
+    // pass.  Without any drift, the first pass ends where it started.
+    let mut sum = 0i64;
+    let (mut low, mut high) = (0, 0);
+    for &num in nums {
+        sum += num as i64;
+        low = low.min(sum);
+        high = high.max(sum);
+    }
+    let passes = if sum == 0 { 1 } else { (high - low) / sum.abs() + 2 };
+    let limit = passes as usize * nums.len();
+
+    let mut seen = HashSet::new();
+    seen.insert(0);
+
+    let mut cur = 0i64;
+    let mut pos = 0;
+    for iters in 1 ..= limit {
+        cur += nums[pos] as i64;
+        if !seen.insert(cur) {
+            return Ok((cur, iters));
+        }
+
+        pos += 1;
+        if pos >= nums.len() {
+            pos = 0;
+        }
+    }
+    Err(Error::solve("No frequency is ever reached twice"))
+}
+
+/// Parse a single frequency change, which must have an explicit sign.
+fn parse_change(line: &Line) -> result::Result<i32, ParseError> {
+    let text = &line.text;
+    match text.as_bytes().first() {
+        Some(b'+') | Some(b'-') => (),
+        _ => return Err(line.error(0, "expecting a change starting with '+' or '-'")),
+    }
+    match text[1..].bytes().position(|b| !b.is_ascii_digit()) {
+        Some(pos) => return Err(line.error(pos + 1, "expecting a digit")),
+        None if text.len() == 1 => return Err(line.error(1, "expecting a digit")),
+        None => (),
+    }
+    line.parse_at(0, text)
+}
+
+/// Read the frequency changes, one per line, such as "+7" or "-12".
+pub fn get_input(input: &InputSource) -> Result<Vec<i32>> {
+    let lines = input.lines("day01", "frequencies.txt")?;
+
+    Ok(lines.iter().map(parse_change).collect::<result::Result<_, _>>()?)
+}
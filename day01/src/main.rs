@@ -0,0 +1,13 @@
+use common::{InputSource, Result, Solver};
+use day01::Day01;
+
+fn main() {
+    common::run(try_main);
+}
+
+fn try_main() -> Result<()> {
+    let input = InputSource::from_args()?;
+    println!("1: {}", Day01.part1(&input)?);
+    println!("2: {}", Day01.part2(&input)?);
+    Ok(())
+}
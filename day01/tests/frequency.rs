@@ -0,0 +1,62 @@
+use common::{Answer, Error, InputSource, Solver};
+use day01::{first_repeat, Day01};
+
+fn input(text: &str) -> InputSource {
+    InputSource::Text(text.to_string())
+}
+
+#[test]
+fn examples() {
+    let sums = [
+        ("+1\n-2\n+3\n+1\n", 3),
+        ("+1\n+1\n+1\n", 3),
+        ("+1\n+1\n-2\n", 0),
+        ("-1\n-2\n-3\n", -6),
+    ];
+    for &(text, answer) in &sums {
+        assert_eq!(Day01.part1(&input(text)).unwrap(), Answer::from(answer as i64), "{:?}", text);
+    }
+
+    let repeats = [
+        ("+1\n-2\n+3\n+1\n", 2),
+        ("+1\n-1\n", 0),
+        ("+3\n+3\n+4\n-2\n-4\n", 10),
+        ("-6\n+3\n+8\n+5\n-6\n", 5),
+        ("+7\n+7\n-2\n-7\n-4\n", 14),
+    ];
+    for &(text, answer) in &repeats {
+        assert_eq!(Day01.part2(&input(text)).unwrap(), Answer::from(answer as i64), "{:?}", text);
+    }
+}
+
+#[test]
+fn no_repeat() {
+    for &nums in &[&[1][..], &[1, 1], &[3, -1]] {
+        match first_repeat(nums) {
+            Err(Error::Solve(message)) => assert_eq!(message, "No frequency is ever reached twice"),
+            other => panic!("{:?}: expected an error, got {:?}", nums, other.map_err(|e| e.to_string())),
+        }
+    }
+
+    // Repeats that take many passes are still found.
+    assert_eq!(first_repeat(&[1000, -999]).unwrap().0, 1000);
+    assert!(first_repeat(&[]).is_err());
+}
+
+#[test]
+fn malformed_lines() {
+    let cases = [
+        ("+1\n7\n", 2, 1, "expecting a change starting with '+' or '-'"),
+        ("+1\n-\n", 2, 2, "expecting a digit"),
+        ("+12x\n", 1, 4, "expecting a digit"),
+        ("+1\n\n", 2, 1, "expecting a change starting with '+' or '-'"),
+    ];
+    for &(text, line, column, message) in &cases {
+        match Day01.part1(&input(text)) {
+            Err(Error::Parse(err)) => {
+                assert_eq!((err.line, err.column, err.message.as_str()), (line, column, message), "{:?}", text);
+            }
+            other => panic!("{:?}: expected a parse error, got {:?}", text, other.map_err(|e| e.to_string())),
+        }
+    }
+}
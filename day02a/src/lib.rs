@@ -0,0 +1,39 @@
+use itertools::Itertools;
+use std::{
+    io::BufRead,
+};
+use common::{InputSource, Result};
+
+/// Compute the checksum of the box ids: the number of ids with some letter
+/// appearing exactly twice, multiplied by the number with some letter
+/// appearing exactly three times.
+pub fn checksum(ids: &[String]) -> u32 {
+    let mut twos = 0;
+    let mut threes = 0;
+
+    for id in ids {
+        let mut this_two = 0;
+        let mut this_three = 0;
+        let mut sorted: Vec<char> = id.chars().collect();
+        sorted.sort();
+
+        for (_, grp) in &sorted.into_iter().group_by(|&x| x) {
+            match grp.count() {
+                2 => this_two = 1,
+                3 => this_three = 1,
+                _ => (),
+            }
+        }
+        twos += this_two;
+        threes += this_three;
+    }
+
+    twos * threes
+}
+
+/// Read all of the lines from the input file.
+pub fn get_input(input: &InputSource) -> Result<Vec<String>> {
+    let f = input.open("day02", "ids.txt")?;
+
+    f.lines().map(|line| Ok(line?)).collect()
+}
@@ -0,0 +1,45 @@
+use std::{
+    io::BufRead,
+};
+use common::{InputSource, Result};
+
+/// Find the two box ids that differ by exactly one character, and return
+/// the characters they have in common.
+pub fn solve(lines: &[String]) -> Option<String> {
+    for outer in 0 .. lines.len() {
+        for inner in outer + 1 .. lines.len() {
+            if delta(&lines[outer], &lines[inner]) == 1 {
+                return Some(common(&lines[outer], &lines[inner]));
+            }
+        }
+    }
+    None
+}
+
+fn delta(a: &str, b: &str) -> u32 {
+    a.chars().zip(b.chars()).map(|(aa, bb)| {
+        if aa == bb {
+            0
+        } else {
+            1
+        }
+    }).sum()
+}
+
+fn common(a: &str, b: &str) -> String {
+    let mut result = String::new();
+
+    for (aa, bb) in a.chars().zip(b.chars()) {
+        if aa == bb {
+            result.push(aa);
+        }
+    }
+    result
+}
+
+/// Read all of the lines from the input file.
+pub fn get_input(input: &InputSource) -> Result<Vec<String>> {
+    let f = input.open("day02", "ids.txt")?;
+
+    f.lines().map(|line| Ok(line?)).collect()
+}
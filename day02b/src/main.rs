@@ -1,51 +1,17 @@
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    result,
-};
+use common::{InputSource, Result};
+use day02b::{get_input, solve};
 
-type Result<T> = result::Result<T, failure::Error>;
-
-fn main() -> Result<()> {
-    let lines = get_input()?;
-
-    'outside:
-    for outer in 0 .. lines.len() {
-        for inner in outer + 1 .. lines.len() {
-            if delta(&lines[outer], &lines[inner]) == 1 {
-                show(&lines[outer], &lines[inner]);
-                break 'outside;
-            }
-        }
-    }
-
-    Ok(())
+fn main() {
+    common::run(try_main);
 }
 
-fn delta(a: &str, b: &str) -> u32 {
-    a.chars().zip(b.chars()).map(|(aa, bb)| {
-        if aa == bb {
-            0
-        } else {
-            1
-        }
-    }).sum()
-}
-
-fn show(a: &str, b: &str) {
-    let mut result = String::new();
-
-    for (aa, bb) in a.chars().zip(b.chars()) {
-        if aa == bb {
-            result.push(aa);
-        }
+fn try_main() -> Result<()> {
+    let input = InputSource::from_args()?;
+    let lines = get_input(&input)?;
+    match solve(&lines) {
+        Some(result) => println!("{:?}", result),
+        None => println!("No match"),
     }
-    println!("{:?}", result);
-}
 
-/// Read all of the lines from the input file.
-fn get_input() -> Result<Vec<String>> {
-    let f = BufReader::new(File::open("ids.txt")?);
-
-    f.lines().map(|line| Ok(line?)).collect()
+    Ok(())
 }
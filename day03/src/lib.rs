@@ -0,0 +1,125 @@
+// TODO: Narrow this down to what we use.
+use nom::{
+    named,
+    eof,
+    do_parse, tag, take_while1,
+    map_res,
+    space, is_digit,
+    types::CompleteByteSlice,
+};
+
+use std::{
+    error,
+    result,
+};
+
+use common::{Answer, Bounds, Error, Grid, InputSource, Point, Result, Solver};
+
+pub struct Day03;
+
+impl Solver for Day03 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let cuts = get_input(input)?;
+        let fabric = coverage(&cuts);
+        Ok(fabric.iter().filter(|&(_, &count)| count > 1).count().into())
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let cuts = get_input(input)?;
+        let fabric = coverage(&cuts);
+
+        // To solve the second part, revisit the cuts, and find one where
+        // every square is covered only by that cut.
+        for cut in &cuts {
+            if cut.area().points().all(|p| fabric[p] == 1) {
+                return Ok(cut.num.into());
+            }
+        }
+
+        Err(Error::solve("Every cut overlaps another"))
+    }
+}
+
+/// Count how many cuts cover each square of the fabric.
+fn coverage(cuts: &[Pos]) -> Grid<u32> {
+    let bounds = Bounds::from_points(cuts.iter().flat_map(|cut| {
+        let area = cut.area();
+        vec![area.min, area.max]
+    })).unwrap_or_else(|| Bounds::with_size(0, 0));
+
+    let mut fabric = Grid::new(bounds, 0);
+    for cut in cuts {
+        for p in cut.area().points() {
+            fabric[p] += 1;
+        }
+    }
+
+    fabric
+}
+
+fn get_input(input: &InputSource) -> Result<Vec<Pos>> {
+    let lines = input.lines("day03", "cuts.txt")?;
+
+    lines.iter().map(|line| {
+        // The parser's error borrows from the line, but only the position
+        // is useful, so report it as a ParseError at that column.
+        match parse_cut(CompleteByteSlice(line.text.as_bytes())) {
+            Ok((_, pos)) => Ok(pos),
+            Err(e) => Err(line.error(error_offset(&line.text, &e), "invalid cut").into()),
+        }
+    }).collect()
+}
+
+/// Determine how far into the line the parser got before it failed.
+fn error_offset(text: &str, err: &nom::Err<CompleteByteSlice>) -> usize {
+    match err {
+        nom::Err::Error(nom::Context::Code(rest, _)) |
+        nom::Err::Failure(nom::Context::Code(rest, _)) => text.len() - rest.0.len(),
+        nom::Err::Incomplete(_) => text.len(),
+    }
+}
+
+#[derive(Debug)]
+struct Pos {
+    num: i32,
+    x: i32,
+    y: i32,
+    w: i32,
+    h: i32,
+}
+
+impl Pos {
+    /// The squares covered by this cut.
+    fn area(&self) -> Bounds {
+        let min = Point::new(self.x as i64, self.y as i64);
+        Bounds::new(min, min + Point::new(self.w as i64 - 1, self.h as i64 - 1))
+    }
+}
+
+// Parser for the cuts.
+named!(parse_cut(CompleteByteSlice) -> Pos,
+    do_parse!(
+        tag!("#") >>
+        num: decimal >> space >>
+        tag!("@") >> space >>
+        x: decimal >> tag!(",") >>
+        y: decimal >> tag!(":") >> space >>
+        w: decimal >> tag!("x") >>
+        h: decimal >> eof!() >>
+        (
+            Pos {
+                num,
+                x,
+                y,
+                w,
+                h,
+            }
+        )
+    ));
+
+named!(decimal(CompleteByteSlice) -> i32,
+       map_res!(take_while1!(is_digit), from_decimal));
+
+fn from_decimal(input: CompleteByteSlice) -> result::Result<i32, Box<dyn error::Error>> {
+    Ok(std::str::from_utf8(input.0)?.parse()?)
+}
@@ -0,0 +1,184 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+};
+use chrono::{
+    Timelike,
+};
+use common::{Answer, Error, InputSource, Result, Solver};
+use crate::parser::{
+    Event,
+    Op,
+    Parser,
+};
+
+mod parser;
+
+pub struct Day04;
+
+impl Solver for Day04 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let evts = get_sorted_input(input)?;
+        let (a, b) = strat1(&evts)?;
+        Ok((a * b).into())
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let evts = get_sorted_input(input)?;
+        let (a, b) = strat2(&evts)?;
+        Ok((a * b).into())
+    }
+}
+
+fn strat1(events: &[Event]) -> Result<(u32, u32)> {
+    // Current guard.
+    let mut current = None;
+
+    // All of the guards.
+    let mut all = AllGuards(HashMap::new());
+
+    for ev in events {
+        match ev.op {
+            Op::Shift(gnum) => {
+                current = Some(gnum);
+            },
+            Op::Sleeps => all.get_mut(on_duty(current)?).sleep(ev.time.minute())?,
+            Op::Wakes => all.get_mut(on_duty(current)?).wake(ev.time.minute())?,
+        }
+    }
+
+    // Get all of the guards, and then sort by total time slept.
+    let mut all: Vec<_> = all.0.drain().map(|(_, g)| g).collect();
+    all.sort_by_key(|g| Reverse(g.total_sleep));
+
+    let best = all.first().ok_or_else(no_sleep)?;
+    Ok((best.num, best.best_minute()))
+}
+
+/// The guard on duty, or an error if an event comes before any shift
+/// starts.
+fn on_duty(current: Option<u32>) -> Result<u32> {
+    current.ok_or_else(|| Error::solve("Event before the first guard's shift"))
+}
+
+fn no_sleep() -> Error {
+    Error::solve("no guard ever sleeps")
+}
+
+/// For strategy 2, which guard is most frequently asleep on the same
+/// minute.
+fn strat2(events: &[Event]) -> Result<(u32, u32)> {
+    // For each minute, maintain a mapping by guard ID to a count for that
+    // guard.
+    let mut mins: Vec<HashMap<u32, u32>> = vec![HashMap::new(); 60];
+    let mut current = None;
+    let mut to_sleep = 0;
+
+    for ev in events {
+        match ev.op {
+            Op::Shift(gnum) => current = Some(gnum),
+            Op::Sleeps => {
+                on_duty(current)?;
+                to_sleep = ev.time.minute();
+            }
+            Op::Wakes => {
+                let gnum = on_duty(current)?;
+                for min in to_sleep .. ev.time.minute() {
+                    *(mins[min as usize].entry(gnum).or_insert(0)) += 1;
+                }
+            }
+        }
+    }
+
+    // Convert each minute to a vector, sorted by the guard with the most
+    // sleep first.
+    let mins = mins.into_iter().map(|m| {
+        let mut mm: Vec<_> = m.iter().map(|(&a, &b)| (a, b)).collect();
+        mm.sort_by_key(|m| Reverse(m.1));
+        mm
+    });
+
+    // Now convert this to a vector with the index at first, and sort.
+    let mins: Vec<_> = mins.into_iter().enumerate().collect();
+
+    // Eliminate any minutes that have no sleeping guards.
+    let mut mins: Vec<_> = mins.into_iter().filter(|x| !x.1.is_empty()).collect();
+    mins.sort_by(|a, b| b.1[0].1.cmp(&a.1[0].1));
+
+    let (minute, guards) = mins.first().ok_or_else(no_sleep)?;
+    Ok((guards[0].0, *minute as u32))
+}
+
+/// All of the guards are kept as a map of this structure.
+struct AllGuards(HashMap<u32, Guard>);
+
+impl AllGuards {
+    /// Return a mutable reference to the given guard.  A new guard will be
+    /// inserted if necessary.
+    fn get_mut(&mut self, gnum: u32) -> &mut Guard {
+        self.0.entry(gnum).or_insert_with(|| Guard::new(gnum))
+    }
+}
+
+/// Tracker for an individual guard.
+struct Guard {
+    num: u32,
+    total_sleep: u32,
+    slept: Option<u32>,
+    minute_count: Vec<u32>,
+}
+
+impl Guard {
+    fn new(num: u32) -> Guard {
+        Guard {
+            num,
+            total_sleep: 0,
+            slept: None,
+            minute_count: vec![0; 60],
+        }
+    }
+
+    fn sleep(&mut self, minute: u32) -> Result<()> {
+        match self.slept {
+            None => self.slept = Some(minute),
+            Some(_) => return Err(Error::solve(format!("Guard #{} falls asleep while asleep", self.num))),
+        }
+        Ok(())
+    }
+
+    fn wake(&mut self, minute: u32) -> Result<()> {
+        match self.slept {
+            None => return Err(Error::solve(format!("Guard #{} wakes up while awake", self.num))),
+            Some(before) => {
+                self.total_sleep += minute - before;
+                self.slept = None;
+                for i in before .. minute {
+                    self.minute_count[i as usize] += 1;
+                }
+            },
+        }
+        Ok(())
+    }
+
+    // Find the minute the guard is most asleep.
+    fn best_minute(&self) -> u32 {
+        let mut mins: Vec<_> = self.minute_count.iter().cloned().enumerate().collect();
+        mins.sort_by_key(|m| Reverse(m.1));
+
+        mins[0].0 as u32
+    }
+}
+
+/// Read the events, sorted into time order.
+fn get_sorted_input(input: &InputSource) -> Result<Vec<Event>> {
+    let mut evts = get_input(input)?;
+    evts.sort_by_key(|x| x.time);
+    Ok(evts)
+}
+
+fn get_input(input: &InputSource) -> Result<Vec<Event>> {
+    let p = Parser::new();
+    let lines = input.lines("day04", "shift.txt")?;
+
+    lines.iter().map(|line| Ok(p.parse_line(line)?)).collect()
+}
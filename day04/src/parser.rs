@@ -5,8 +5,11 @@ use chrono::naive::{
     NaiveTime,
     NaiveDateTime,
 };
-use crate::Result;
+use common::{Line, ParseError};
 use regex::Regex;
+use std::result;
+
+type Result<T> = result::Result<T, ParseError>;
 
 /// The event we care about:
 #[derive(Debug)]
@@ -36,34 +39,40 @@ impl Parser {
         }
     }
 
-    pub fn parse_line(&self, line: &str) -> Result<Event> {
-        let (dt, line) = match self.time_re.captures(line) {
-            None => panic!("Invalid line"),
+    pub fn parse_line(&self, line: &Line) -> Result<Event> {
+        let (dt, text, text_pos) = match self.time_re.captures(&line.text) {
+            None => return Err(line.error_line("expecting \"[yyyy-mm-dd hh:mm] event\"")),
             Some(cap) => {
-                let date = NaiveDate::from_ymd(cap[1].parse().unwrap(),
-                                               cap[2].parse().unwrap(),
-                                               cap[3].parse().unwrap());
-                let time = NaiveTime::from_hms(cap[4].parse().unwrap(),
-                                               cap[5].parse().unwrap(),
-                                               0);
-                (NaiveDateTime::new(date, time), cap[6].to_string())
+                let date = NaiveDate::from_ymd_opt(line.parse_cap(&cap, 1)?,
+                                                   line.parse_cap(&cap, 2)?,
+                                                   line.parse_cap(&cap, 3)?)
+                    .ok_or_else(|| line.error(1, "invalid date"))?;
+                let time = NaiveTime::from_hms_opt(line.parse_cap(&cap, 4)?,
+                                                   line.parse_cap(&cap, 5)?,
+                                                   0)
+                    .ok_or_else(|| line.error(12, "invalid time"))?;
+                let m = cap.get(6).unwrap();
+                (NaiveDateTime::new(date, time), m.as_str(), m.start())
             }
         };
 
-        let op = match &line[..] {
+        let op = match text {
             "wakes up" => Op::Wakes,
             "falls asleep" => Op::Sleeps,
             text => {
                 match self.shift_re.captures(text) {
-                    None => panic!("Invalid line {:?}", text),
-                    Some(cap) => Op::Shift(cap[1].parse().unwrap()),
+                    None => return Err(line.error(text_pos, "unknown event")),
+                    Some(cap) => {
+                        let m = cap.get(1).unwrap();
+                        Op::Shift(line.parse_at(text_pos + m.start(), m.as_str())?)
+                    }
                 }
             }
         };
 
         Ok(Event {
             time: dt,
-            op: op,
+            op,
         })
     }
 }
@@ -0,0 +1,50 @@
+use common::{Answer, Error, InputSource, Solver};
+use day04::Day04;
+
+fn input(text: &str) -> InputSource {
+    InputSource::Text(text.to_string())
+}
+
+const EXAMPLE: &str = "\
+[1518-11-01 00:00] Guard #10 begins shift
+[1518-11-01 00:05] falls asleep
+[1518-11-01 00:25] wakes up
+[1518-11-01 00:30] falls asleep
+[1518-11-01 00:55] wakes up
+[1518-11-01 23:58] Guard #99 begins shift
+[1518-11-02 00:40] falls asleep
+[1518-11-02 00:50] wakes up
+[1518-11-03 00:05] Guard #10 begins shift
+[1518-11-03 00:24] falls asleep
+[1518-11-03 00:29] wakes up
+[1518-11-04 00:02] Guard #99 begins shift
+[1518-11-04 00:36] falls asleep
+[1518-11-04 00:46] wakes up
+[1518-11-05 00:03] Guard #99 begins shift
+[1518-11-05 00:45] falls asleep
+[1518-11-05 00:55] wakes up
+";
+
+#[test]
+fn example() {
+    assert_eq!(Day04.part1(&input(EXAMPLE)).unwrap(), Answer::from(240));
+    assert_eq!(Day04.part2(&input(EXAMPLE)).unwrap(), Answer::from(4455));
+}
+
+#[test]
+fn bad_journals() {
+    let cases = [
+        ("[1518-11-01 00:00] Guard #10 begins shift\n", "no guard ever sleeps"),
+        ("", "no guard ever sleeps"),
+        ("[1518-11-01 00:05] falls asleep\n[1518-11-01 00:25] wakes up\n", "Event before the first guard's shift"),
+    ];
+    for &(text, message) in &cases {
+        for part in 1 ..= 2 {
+            let result = if part == 1 { Day04.part1(&input(text)) } else { Day04.part2(&input(text)) };
+            match result {
+                Err(Error::Solve(err)) => assert_eq!(err, message, "part {}: {:?}", part, text),
+                other => panic!("part {}: {:?}: expected an error, got {:?}", part, text, other.map_err(|e| e.to_string())),
+            }
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use regex::Regex;
+use std::{
+    borrow::Cow,
+};
+use common::{eof_error, Answer, InputSource, Result, Solver};
+
+pub struct Day05;
+
+impl Solver for Day05 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let rem = Remover::new();
+
+        let work = get_input(input)?;
+        Ok(rem.remove(&work).len().into())
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let rem = Remover::new();
+
+        let work = get_input(input)?;
+
+        // Try replacing each character.
+        let mut best = work.len();
+        for ch in b'a' ..= b'z' {
+            let w1 = work.replace(ch as char, "");
+            let w2 = w1.replace((ch - 32) as char, "");
+            let this_len = rem.remove(&w2).len();
+            best = best.min(this_len);
+            // println!("{}: {}", ch as char, this_len);
+        }
+        Ok(best.into())
+    }
+}
+
+struct Remover {
+    sub_re: Regex,
+}
+
+impl Remover {
+    fn new() -> Remover {
+        // Build a regex up to eliminate the interesting pairs.
+        // Rust gets annoying here because of Unicode.  We'll just do this with
+        // bytes.
+        let mut pattern = String::new();
+        pattern.push('(');
+        for ch in b'a' ..= b'z' {
+            if pattern.len() > 1 {
+                pattern.push('|');
+            }
+            pattern.push(ch as char);
+            pattern.push((ch - 32) as char);
+            pattern.push('|');
+            pattern.push((ch - 32) as char);
+            pattern.push(ch as char);
+        }
+        pattern.push(')');
+        // println!("pattern: {:?}", pattern);
+
+        Remover {
+            sub_re: Regex::new(&pattern).unwrap(),
+        }
+    }
+
+    fn remove(&self, text: &str) -> String {
+        let mut work = text.to_owned();
+        // It should be the same whether the replacements happen at the
+        // beginning, or make as many passes through the string as we can.
+        // It returns an owned string if it made any changes.
+        while let Cow::Owned(txt) = self.sub_re.replace_all(&work, "") {
+            work = txt;
+        }
+        work
+    }
+}
+
+/// Read the input (which is on a single line).
+fn get_input(input: &InputSource) -> Result<String> {
+    let lines = input.lines("day05", "polymer.txt")?;
+
+    match lines.into_iter().next() {
+        Some(line) => Ok(line.text),
+        None => Err(eof_error(&input.display_name("day05", "polymer.txt"), 0,
+                              "expecting the polymer").into()),
+    }
+}
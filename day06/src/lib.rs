@@ -0,0 +1,212 @@
+use regex::Regex;
+use std::{
+    collections::{
+        BTreeMap,
+        HashMap,
+        HashSet,
+    },
+};
+use common::{Answer, Bounds, Error, InputSource, Point, Result};
+
+pub struct Day06;
+
+impl common::Solver for Day06 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let work = get_input(input)?;
+        let solve = Solver::new(&work)?;
+        // println!("solve: {:?}", solve);
+
+        match solve.solve1() {
+            Some(count) => Ok(count.into()),
+            None => Err(Error::solve("No result")),
+        }
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let work = get_input(input)?;
+        let solve = Solver::new(&work)?;
+
+        Ok(solve.solve2(10000).into())
+    }
+}
+
+#[derive(Debug)]
+struct Solver {
+    // Bounds of the problem space.
+    bounds: Bounds,
+
+    // The coordinates themselves.  This is a map, just to give the
+    // coordinates a label.
+    coords: BTreeMap<usize, Point>,
+}
+
+impl Solver {
+    fn new(coords: &[Point]) -> Result<Solver> {
+        let bounds = Bounds::from_points(coords.iter().cloned())
+            .ok_or_else(|| Error::solve("No coordinates given"))?;
+        Ok(Solver {
+            bounds,
+            coords: coords.iter().cloned().enumerate().collect(),
+        })
+    }
+
+    /// Solve the first part of the problem.  We search for the area around
+    /// each coordinate that is closest to that coordinate.  Don't count
+    /// any cells that are equidistant to two coordinates.  In addition,
+    /// discard any coordinate that has a nearest cell on the outer
+    /// boundary, as these will be unbounded.  Returns the size of the
+    /// largest area, or None if every area is unbounded.
+    fn solve1(&self) -> Option<usize> {
+        // Ones we find on the edge will be discarded here.
+        let mut discards: HashSet<usize> = HashSet::new();
+
+        // Mapping between coordinate indices and the count of how many
+        // cells have been seen.
+        let mut counts: HashMap<usize, usize> = HashMap::new();
+
+        for p in self.bounds.points() {
+            match self.closest(p) {
+                None => (),
+                Some(cell) => {
+                    if self.bounds.on_edge(p) {
+                        // This is on edge, ignore this cell entirely.
+                        discards.insert(cell);
+                    } else {
+                        *counts.entry(cell).or_insert(0) += 1;
+                    }
+                }
+            }
+            // println!("{:?} = {:?}", p, closest);
+        }
+
+        for d in discards.into_iter() {
+            counts.remove(&d);
+        }
+
+        // println!("{:?}", counts);
+
+        // The result is the cell with the largest count.
+        counts.values().max().cloned()
+    }
+
+    /// Solve the second part of the problem.  We're trying to find places
+    /// where the sum of the distance to each coord is less than a given
+    /// value.  The region given is large (10000), so we have to be a bit
+    /// creative with how we determine the search area.
+    fn solve2(&self, bound: i64) -> usize {
+
+        // To start, let's find the center of all of the coordinates.  This
+        // hopefully is within bounds.
+        let xsum = self.coords.values().map(|c| c.x as f64).sum::<f64>() /
+            (self.coords.len() as f64);
+        let ysum = self.coords.values().map(|c| c.y as f64).sum::<f64>() /
+            (self.coords.len() as f64);
+
+        let xbase = xsum as i64;
+        let ybase = ysum as i64;
+
+        // println!("x: {}, y: {}", xbase, ybase);
+        // println!("cum: {}", self.cum_distance(Point::new(xbase, ybase)));
+
+        let mut total = 0usize;
+
+        // Walk by y coordinates 'up' until we run out of distance.
+        for dy in 0.. {
+            let y = ybase - dy;
+
+            let sum = self.hwalk(bound, y, xbase);
+            if sum == 0 {
+                break;
+            }
+
+            total += sum;
+        }
+
+        for dy in 1.. {
+            let y = ybase + dy;
+
+            let sum = self.hwalk(bound, y, xbase);
+            if sum == 0 {
+                break;
+            }
+
+            total += sum;
+        }
+
+        total
+    }
+
+    /// Walk across x coordinates determining how many are "inside".
+    fn hwalk(&self, bound: i64, y: i64, xbase: i64) -> usize {
+        let mut total = 0usize;
+
+        for dx in 0.. {
+            let x = xbase - dx;
+            let dist = self.cum_distance(Point::new(x, y));
+            if dist >= bound {
+                break;
+            }
+
+            total += 1;
+        }
+
+        for dx in 1.. {
+            let x = xbase + dx;
+            let dist = self.cum_distance(Point::new(x, y));
+            if dist >= bound {
+                break;
+            }
+
+            total += 1;
+        }
+
+        total
+    }
+
+    /// Find the coordinate closest to the given cell.  If it is not
+    /// unique, return None.
+    fn closest(&self, cell: Point) -> Option<usize> {
+        let mut unique = false;
+        let mut best = None;
+        let mut best_value = i64::MAX;
+
+        for (&k, &pos) in self.coords.iter() {
+            let dist = cell.manhattan(pos);
+            if dist < best_value {
+                unique = true;
+                best = Some(k);
+                best_value = dist;
+            } else if dist == best_value {
+                unique = false;
+            }
+        }
+
+        if unique {
+            Some(best.unwrap())
+        } else {
+            None
+        }
+    }
+
+    /// Find the cumulative distance to all of the coords from the given
+    /// point.
+    fn cum_distance(&self, cell: Point) -> i64 {
+        self.coords.values().map(|&pos| cell.manhattan(pos)).sum()
+    }
+}
+
+fn get_input(input: &InputSource) -> Result<Vec<Point>> {
+    let re = Regex::new(r"^(\d+), (\d+)$")?;
+    let lines = input.lines("day06", "coords.txt")?;
+
+    lines.iter().map(|line| {
+        match re.captures(&line.text) {
+            None => Err(line.error_line("expecting \"x, y\"").into()),
+            Some(cap) => {
+                let x = line.parse_cap(&cap, 1)?;
+                let y = line.parse_cap(&cap, 2)?;
+                Ok(Point::new(x, y))
+            }
+        }
+    }).collect()
+}
@@ -0,0 +1,90 @@
+use common::{eof_error, Answer, InputSource, Result, Solver};
+
+pub struct Day08;
+
+impl Solver for Day08 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let tree = get_input(input)?;
+
+        Ok(tree.metadata_total().into())
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let tree = get_input(input)?;
+
+        Ok(tree.value().into())
+    }
+}
+
+#[derive(Debug)]
+struct Tree {
+    children: Vec<Tree>,
+    metadata: Vec<usize>,
+}
+
+impl Tree {
+    // Decode an iterator over codes into a tree.  Returns None if the
+    // codes run out before the tree is complete.
+    fn from_codes<I>(source: &mut I) -> Option<Tree> where
+        I: Iterator<Item=usize>,
+    {
+        let nchildren = source.next()?;
+        let nmeta = source.next()?;
+
+        let children = (0 .. nchildren).map(|_| Tree::from_codes(source)).collect::<Option<_>>()?;
+        let meta = (0 .. nmeta).map(|_| source.next()).collect::<Option<_>>()?;
+
+        Some(Tree{
+            children,
+            metadata: meta,
+        })
+    }
+
+    /// Get the total metadata.
+    fn metadata_total(&self) -> usize {
+        self.children.iter().map(|c| c.metadata_total()).sum::<usize>() +
+            self.metadata.iter().sum::<usize>()
+    }
+
+    /// Get the 'value' as defined in the problem.  If there are no
+    /// children, it is the sum of the metadata.  If there are children,
+    /// use the metadata as 1-based indices into the children.  Skip any
+    /// that aren't valid.
+    fn value(&self) -> usize {
+        if self.children.is_empty() {
+            self.metadata.iter().sum::<usize>()
+        } else {
+            self.metadata.iter().map(|&m| {
+                if m == 0 || m > self.children.len() {
+                    0
+                } else {
+                    self.children[m-1].value()
+                }
+            }).sum::<usize>()
+        }
+    }
+}
+
+/// Read the license, which is a single line of numbers, and decode it into
+/// the tree.
+fn get_input(input: &InputSource) -> Result<Tree> {
+    let lines = input.lines("day08", "license.txt")?;
+
+    let line = match lines.first() {
+        Some(line) => line,
+        None => return Err(eof_error(&input.display_name("day08", "license.txt"), 0,
+                                     "expecting the license").into()),
+    };
+
+    let mut result = vec![];
+    let mut offset = 0;
+    for n in line.text.split(' ') {
+        result.push(line.parse_at(offset, n)?);
+        offset += n.len() + 1;
+    }
+
+    match Tree::from_codes(&mut result.into_iter()) {
+        Some(tree) => Ok(tree),
+        None => Err(line.error(line.text.len(), "license ends before the tree is complete").into()),
+    }
+}
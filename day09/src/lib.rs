@@ -0,0 +1,186 @@
+use regex::Regex;
+
+use common::{eof_error, Answer, InputSource, Line, Result, Solver};
+
+pub struct Day09;
+
+impl Solver for Day09 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let game = get_input(input)?;
+        Ok(game.play().high_score().into())
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        // The second part is the same game, but with 100 times as many
+        // marbles.
+        let mut game = get_input(input)?;
+        game.last_marble *= 100;
+        Ok(game.play().high_score().into())
+    }
+}
+
+/// The rules of a marble game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MarbleGame {
+    pub players: usize,
+
+    /// The value of the last marble played.
+    pub last_marble: usize,
+
+    /// Marbles that are a multiple of this are kept, rather than placed.
+    pub multiple: usize,
+
+    /// When a marble is kept, the marble this many counter-clockwise from
+    /// the current marble is removed and kept as well.
+    pub rewind: usize,
+}
+
+/// The result of playing a game.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    /// The score of each player, with the first player at index 0.
+    pub scores: Vec<usize>,
+}
+
+impl MarbleGame {
+    /// A game with the usual scoring rules.
+    pub fn new(players: usize, last_marble: usize) -> MarbleGame {
+        MarbleGame {
+            players,
+            last_marble,
+            multiple: 23,
+            rewind: 7,
+        }
+    }
+
+    /// Parse the game description, "N players; last marble is worth M
+    /// points".
+    pub fn parse(line: &Line) -> Result<MarbleGame> {
+        let re = Regex::new(r"^(\d+) players; last marble is worth (\d+) points$")?;
+        let cap = re.captures(&line.text).ok_or_else(|| {
+            line.error_line("expecting \"N players; last marble is worth M points\"")
+        })?;
+        let players = line.parse_cap(&cap, 1)?;
+        if players == 0 {
+            return Err(line.error(0, "there must be at least one player").into());
+        }
+        Ok(MarbleGame::new(players, line.parse_cap(&cap, 2)?))
+    }
+
+    /// Play the game until the last marble is placed.
+    pub fn play(&self) -> Outcome {
+        let mut circle = Circle::new(self.last_marble);
+        let mut scores = vec![0; self.players];
+        if self.players == 0 {
+            return Outcome { scores };
+        }
+
+        let mut player = 0;
+        for marble in 1 ..= self.last_marble {
+            if self.multiple != 0 && marble % self.multiple == 0 {
+                // First, the current player keeps the marble they would
+                // have placed, adding it to their score.  In addition, the
+                // marble `rewind` marbles counter-clockwise from the
+                // current marble is removed from the circle, and also added
+                // to the current player's score.  The marble located
+                // immediately clockwise of the marble that was removed
+                // becomes the new current marble.
+                circle.back(self.rewind);
+                scores[player] += marble + circle.remove();
+            } else {
+                // Otherwise, the marble goes between the marbles 1 and 2
+                // clockwise of the current marble, and becomes current.
+                circle.forward(1);
+                circle.insert(marble);
+            }
+
+            player += 1;
+            if player >= self.players {
+                player = 0;
+            }
+        }
+
+        Outcome { scores }
+    }
+}
+
+impl Outcome {
+    /// The winning player's index and score.  Ties go to the earlier
+    /// player.
+    pub fn winner(&self) -> Option<(usize, usize)> {
+        self.scores.iter().cloned().enumerate()
+            .fold(None, |best, (i, score)| match best {
+                Some((_, top)) if top >= score => best,
+                _ => Some((i, score)),
+            })
+    }
+
+    /// The winning score, which is 0 if nobody played.
+    pub fn high_score(&self) -> usize {
+        self.winner().map(|(_, score)| score).unwrap_or(0)
+    }
+}
+
+/// The circle of marbles, as a doubly linked list.  The links are indexed
+/// by marble value, since each marble is only ever in the circle once.
+struct Circle {
+    next: Vec<usize>,
+    prev: Vec<usize>,
+    current: usize,
+}
+
+impl Circle {
+    /// A circle holding only marble 0, with room for marbles up to `last`.
+    fn new(last: usize) -> Circle {
+        Circle {
+            next: vec![0; last + 1],
+            prev: vec![0; last + 1],
+            current: 0,
+        }
+    }
+
+    fn forward(&mut self, count: usize) {
+        for _ in 0 .. count {
+            self.current = self.next[self.current];
+        }
+    }
+
+    fn back(&mut self, count: usize) {
+        for _ in 0 .. count {
+            self.current = self.prev[self.current];
+        }
+    }
+
+    /// Insert a marble clockwise of the current one, and make it current.
+    fn insert(&mut self, marble: usize) {
+        let before = self.current;
+        let after = self.next[before];
+        self.next[before] = marble;
+        self.prev[marble] = before;
+        self.next[marble] = after;
+        self.prev[after] = marble;
+        self.current = marble;
+    }
+
+    /// Remove the current marble, returning its value.  The marble
+    /// clockwise of it becomes current.  If it is the only marble left,
+    /// it stays.
+    fn remove(&mut self) -> usize {
+        let marble = self.current;
+        let before = self.prev[marble];
+        let after = self.next[marble];
+        self.next[before] = after;
+        self.prev[after] = before;
+        self.current = after;
+        marble
+    }
+}
+
+fn get_input(input: &InputSource) -> Result<MarbleGame> {
+    let lines = input.lines("day09", "game.txt")?;
+    match lines.first() {
+        Some(line) => MarbleGame::parse(line),
+        None => Err(eof_error(&input.display_name("day09", "game.txt"), 0,
+                              "expecting the game description").into()),
+    }
+}
@@ -0,0 +1,70 @@
+use common::{Answer, Error, InputSource, Solver};
+use day09::{Day09, MarbleGame, Outcome};
+
+#[test]
+fn examples() {
+    let games = [
+        (9, 25, 32),
+        (10, 1618, 8317),
+        (13, 7999, 146373),
+        (17, 1104, 2764),
+        (21, 6111, 54718),
+        (30, 5807, 37305),
+    ];
+    for &(players, last_marble, score) in &games {
+        let outcome = MarbleGame::new(players, last_marble).play();
+        assert_eq!(outcome.scores.len(), players);
+        assert_eq!(outcome.high_score(), score, "{} players, {} marbles", players, last_marble);
+    }
+
+    // In the worked example, the fifth player keeps marbles 23 and 9.
+    let outcome = MarbleGame::new(9, 25).play();
+    assert_eq!(outcome.scores, vec![0, 0, 0, 0, 32, 0, 0, 0, 0]);
+    assert_eq!(outcome.winner(), Some((4, 32)));
+}
+
+/// Keeping every fifth marble, and taking the one two back, the circle
+/// goes 0 4 2 1 3 before marble 5 takes 3, and 0 9 4 6 2 7 1 8 before
+/// marble 10 takes 8.
+#[test]
+fn other_rules() {
+    let game = MarbleGame { players: 2, last_marble: 10, multiple: 5, rewind: 2 };
+    let outcome = game.play();
+    assert_eq!(outcome.scores, vec![8, 18]);
+    assert_eq!(outcome.winner(), Some((1, 18)));
+
+    // With no multiple, every marble is placed, and nobody scores.
+    let game = MarbleGame { multiple: 0, ..MarbleGame::new(3, 100) };
+    assert_eq!(game.play().scores, vec![0, 0, 0]);
+}
+
+#[test]
+fn winner() {
+    let outcome = Outcome { scores: vec![5, 7, 7, 2] };
+    assert_eq!(outcome.winner(), Some((1, 7)));
+    assert_eq!(outcome.high_score(), 7);
+
+    let outcome = Outcome { scores: vec![] };
+    assert_eq!(outcome.winner(), None);
+    assert_eq!(outcome.high_score(), 0);
+
+    assert_eq!(MarbleGame::new(0, 25).play().winner(), None);
+}
+
+#[test]
+fn parse() {
+    let input = InputSource::Text("10 players; last marble is worth 1618 points\n".to_string());
+    assert_eq!(Day09.part1(&input).unwrap(), Answer::from(8317));
+
+    let cases = [
+        ("0 players; last marble is worth 25 points\n", "there must be at least one player"),
+        ("10 players, last marble is worth 1618 points\n", "expecting \"N players; last marble is worth M points\""),
+        ("", "expecting the game description"),
+    ];
+    for &(text, message) in &cases {
+        match Day09.part1(&InputSource::Text(text.to_string())) {
+            Err(Error::Parse(err)) => assert_eq!(err.message, message, "{:?}", text),
+            other => panic!("{:?}: expected a parse error, got {:?}", text, other.map_err(|e| e.to_string())),
+        }
+    }
+}
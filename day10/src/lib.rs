@@ -0,0 +1,97 @@
+// use image::ColorType;
+use regex::Regex;
+use common::{Answer, Bounds, InputSource, Point, Result, Solver, SparseGrid};
+
+pub struct Day10;
+
+impl Solver for Day10 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let mut points = get_input(input)?;
+
+        let (_size, _) = converge(&mut points);
+        // println!("size: {:?}", _size);
+
+        Ok(Answer::Grid(render(&points)))
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let mut points = get_input(input)?;
+
+        let (_, count) = converge(&mut points);
+        Ok(count.into())
+    }
+}
+
+/// Keep adjusting the image until the size stops shrinking.  Returns the
+/// final size, and the number of steps it took to get there.
+fn converge(points: &mut [Light]) -> (usize, usize) {
+    let mut last_size = usize::MAX;
+    let mut count = 0;
+    if points.is_empty() {
+        return (0, 0);
+    }
+    loop {
+        adjust(points);
+        let nsize = size(points);
+        if nsize > last_size {
+            unadjust(points);
+            break;
+        }
+        last_size = nsize;
+        count += 1
+    }
+    (last_size, count)
+}
+
+fn size(points: &[Light]) -> usize {
+    match Bounds::from_points(points.iter().map(|p| p.pos)) {
+        Some(bounds) => bounds.width().max(bounds.height()),
+        None => 0,
+    }
+}
+
+/// Render the lights as rows of text.
+fn render(points: &[Light]) -> Vec<String> {
+    let lights: SparseGrid<()> = points.iter().map(|p| (p.pos, ())).collect();
+
+    lights.render(|_, light| if light.is_some() { '*' } else { ' ' })
+}
+
+/// Adjust the points for the given movement.
+fn adjust(points: &mut [Light]) {
+    for p in points {
+        p.pos = p.pos + p.vel;
+    }
+}
+
+/// Back out the last adjustment.
+fn unadjust(points: &mut [Light]) {
+    for p in points {
+        p.pos = p.pos - p.vel;
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Light {
+    pos: Point,
+    vel: Point,
+}
+
+fn get_input(input: &InputSource) -> Result<Vec<Light>> {
+    let re = Regex::new(r"^position=< ?(-?\d+),  ?(-?\d+)> velocity=< ?(-?\d+),  ?(-?\d+)>$")?;
+    let lines = input.lines("day10", "lights.txt")?;
+
+    lines.iter().map(|line| {
+        match re.captures(&line.text) {
+            None => Err(line.error_line(
+                    "expecting \"position=<x, y> velocity=<dx, dy>\"").into()),
+            Some(cap) => {
+                let x = line.parse_cap(&cap, 1)?;
+                let y = line.parse_cap(&cap, 2)?;
+                let dx = line.parse_cap(&cap, 3)?;
+                let dy = line.parse_cap(&cap, 4)?;
+                Ok(Light{pos: Point::new(x, y), vel: Point::new(dx, dy)})
+            }
+        }
+    }).collect()
+}
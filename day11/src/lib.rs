@@ -0,0 +1,205 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    ops::RangeInclusive,
+};
+use common::{eof_error, Answer, Bounds, Error, Grid, InputSource, Point, Result, Solver};
+
+pub struct Day11;
+
+const SIZE: usize = 300;
+
+impl Solver for Day11 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let r = Rack::new(get_input(input)?, SIZE, SIZE);
+
+        let best = best(r.best_regions(3, 3, 1))?;
+        Ok(Answer::Coord(best.x, best.y))
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let r = Rack::new(get_input(input)?, SIZE, SIZE);
+
+        // With the summed-area table, each square is constant time, so
+        // just try all of them.
+        let best = best(r.best_squares(1 ..= SIZE as i64, 1))?;
+        Ok(Answer::Text(format!("{},{},{}", best.x, best.y, best.width)))
+    }
+}
+
+/// Read the grid serial number.
+fn get_input(input: &InputSource) -> Result<i32> {
+    let lines = input.lines("day11", "serial.txt")?;
+    match lines.first() {
+        Some(line) => Ok(line.parse_at(0, &line.text)?),
+        None => Err(eof_error(&input.display_name("day11", "serial.txt"), 0,
+                              "expecting the grid serial number").into()),
+    }
+}
+
+fn best(regions: Vec<Region>) -> Result<Region> {
+    regions.into_iter().next().ok_or_else(|| Error::solve("The rack is too small"))
+}
+
+/// A grid of fuel cells.  Cells are numbered from (1, 1) at the top left.
+pub struct Rack {
+    width: i64,
+    height: i64,
+
+    /// A summed-area table of the power levels.  Each entry is the total
+    /// of all of the levels above and to the left of it, inclusive.  Row
+    /// and column 0 are all zero, so that regions along the edges don't
+    /// need special cases.
+    sums: Grid<i64>,
+}
+
+/// A rectangular region of the rack, and its total power.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub x: i64,
+    pub y: i64,
+    pub width: i64,
+    pub height: i64,
+    pub power: i64,
+}
+
+impl Rack {
+    /// Build a rack of the given size, with power levels computed from the
+    /// serial number.
+    pub fn new(serial: i32, width: usize, height: usize) -> Rack {
+        let bounds = Bounds::new(Point::new(1, 1), Point::new(width as i64, height as i64));
+        let levels = Grid::from_fn(bounds, |p| {
+            let rack_id = p.x + 10;
+            ((rack_id * p.y + serial as i64) * rack_id / 100) % 10 - 5
+        });
+        Rack::from_levels(&levels)
+    }
+
+    /// Build a rack with arbitrary power levels.  The top left of the grid
+    /// becomes cell (1, 1).
+    pub fn from_levels(levels: &Grid<i64>) -> Rack {
+        let width = levels.width() as i64;
+        let height = levels.height() as i64;
+        let origin = levels.bounds().min - Point::new(1, 1);
+
+        // Each sum can be built from the ones above and to the left, which
+        // have already been computed, since this is in reading order.
+        let mut sums = Grid::new(Bounds::new(Point::new(0, 0), Point::new(width, height)), 0);
+        for (p, &level) in levels.iter() {
+            let p = p - origin;
+            sums[p] = level + sums[p + Point::UP] + sums[p + Point::LEFT] -
+                sums[p + Point::UP + Point::LEFT];
+        }
+
+        Rack {
+            width,
+            height,
+            sums,
+        }
+    }
+
+    pub fn width(&self) -> i64 {
+        self.width
+    }
+
+    pub fn height(&self) -> i64 {
+        self.height
+    }
+
+    /// The total power of the rectangle with the given top left corner and
+    /// size.  This takes constant time.
+    pub fn power(&self, x: i64, y: i64, width: i64, height: i64) -> i64 {
+        let (x0, y0) = (x - 1, y - 1);
+        let (x1, y1) = (x + width - 1, y + height - 1);
+        self.sums[Point::new(x1, y1)] - self.sums[Point::new(x0, y1)] -
+            self.sums[Point::new(x1, y0)] + self.sums[Point::new(x0, y0)]
+    }
+
+    /// The total power of the square with the given top left corner and
+    /// size.
+    pub fn power_square(&self, x: i64, y: i64, size: i64) -> i64 {
+        self.power(x, y, size, size)
+    }
+
+    /// The `k` regions of the given size with the most power, best first.
+    /// Regions may overlap.
+    pub fn best_regions(&self, width: i64, height: i64, k: usize) -> Vec<Region> {
+        let mut top = TopK::new(k);
+        self.scan(width, height, &mut top);
+        top.into_sorted()
+    }
+
+    /// The `k` squares with the most power, considering each of the given
+    /// sizes, best first.
+    pub fn best_squares(&self, sizes: RangeInclusive<i64>, k: usize) -> Vec<Region> {
+        let mut top = TopK::new(k);
+        for size in sizes {
+            self.scan(size, size, &mut top);
+        }
+        top.into_sorted()
+    }
+
+    /// Offer every region of the given size to `top`.
+    fn scan(&self, width: i64, height: i64, top: &mut TopK) {
+        if width < 1 || height < 1 {
+            return;
+        }
+        for y in 1 ..= self.height - height + 1 {
+            for x in 1 ..= self.width - width + 1 {
+                top.offer(Region {
+                    x, y, width, height,
+                    power: self.power(x, y, width, height),
+                });
+            }
+        }
+    }
+}
+
+/// Regions are ordered by power.  Ties go to the smaller region, and then
+/// to the one that comes first in reading order, so the greatest region is
+/// the first best one a simple scan would find.
+impl Ord for Region {
+    fn cmp(&self, other: &Region) -> Ordering {
+        let key = |r: &Region| (r.power, Reverse((r.width, r.height, r.y, r.x)));
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for Region {
+    fn partial_cmp(&self, other: &Region) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Keeps the best `k` regions offered to it.
+struct TopK {
+    k: usize,
+
+    /// A min-heap, so the worst region kept is the one to drop.
+    heap: BinaryHeap<Reverse<Region>>,
+}
+
+impl TopK {
+    fn new(k: usize) -> TopK {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn offer(&mut self, region: Region) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse(region));
+        } else if let Some(worst) = self.heap.peek() {
+            if region > worst.0 {
+                self.heap.pop();
+                self.heap.push(Reverse(region));
+            }
+        }
+    }
+
+    fn into_sorted(self) -> Vec<Region> {
+        // Sorting the reversed regions ascending puts the best first.
+        self.heap.into_sorted_vec().into_iter().map(|r| r.0).collect()
+    }
+}
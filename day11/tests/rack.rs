@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+use common::{Answer, Bounds, Error, Grid, InputSource, Point, Solver};
+use day11::{Day11, Rack, Region};
+
+/// Some power levels with no pattern to them, for a rack of the given
+/// size.  The grid doesn't start at (1, 1), to check that it is moved.
+fn levels(width: i64, height: i64) -> Grid<i64> {
+    let bounds = Bounds::new(Point::new(-3, 5), Point::new(width - 4, height + 4));
+    Grid::from_fn(bounds, |p| (p.x * 7 + p.y * 13 + p.x * p.y) % 11 - 5)
+}
+
+/// Add up a region of the levels, one cell at a time.
+fn brute_power(levels: &Grid<i64>, x: i64, y: i64, width: i64, height: i64) -> i64 {
+    let origin = levels.bounds().min - Point::new(1, 1);
+    let mut total = 0;
+    for dy in 0 .. height {
+        for dx in 0 .. width {
+            total += levels[origin + Point::new(x + dx, y + dy)];
+        }
+    }
+    total
+}
+
+/// Every region of the given size, found the slow way, best first.
+fn brute_regions(levels: &Grid<i64>, width: i64, height: i64) -> Vec<Region> {
+    let mut regions = vec![];
+    for y in 1 ..= levels.height() as i64 - height + 1 {
+        for x in 1 ..= levels.width() as i64 - width + 1 {
+            regions.push(Region { x, y, width, height, power: brute_power(levels, x, y, width, height) });
+        }
+    }
+    regions.sort_by(|a, b| b.cmp(a));
+    regions
+}
+
+fn check_order(regions: &[Region]) {
+    for pair in regions.windows(2) {
+        assert!(pair[0] > pair[1], "{:?} should come before {:?}", pair[0], pair[1]);
+    }
+    let distinct: HashSet<_> = regions.iter().map(|r| (r.x, r.y, r.width, r.height)).collect();
+    assert_eq!(distinct.len(), regions.len());
+}
+
+#[test]
+fn power_levels() {
+    let cases = [
+        (8, 3, 5, 4),
+        (57, 122, 79, -5),
+        (39, 217, 196, 0),
+        (71, 101, 153, 4),
+    ];
+    for &(serial, x, y, level) in &cases {
+        assert_eq!(Rack::new(serial, 300, 300).power(x, y, 1, 1), level, "serial {}", serial);
+    }
+}
+
+#[test]
+fn sums() {
+    let levels = levels(9, 6);
+    let rack = Rack::from_levels(&levels);
+    assert_eq!((rack.width(), rack.height()), (9, 6));
+
+    for y in 1 ..= 6 {
+        for x in 1 ..= 9 {
+            for height in 1 ..= 6 - y + 1 {
+                for width in 1 ..= 9 - x + 1 {
+                    assert_eq!(rack.power(x, y, width, height), brute_power(&levels, x, y, width, height),
+                               "{}x{} at {},{}", width, height, x, y);
+                }
+            }
+        }
+    }
+    assert_eq!(rack.power_square(2, 3, 4), brute_power(&levels, 2, 3, 4, 4));
+}
+
+#[test]
+fn best_regions() {
+    let levels = levels(9, 6);
+    let rack = Rack::from_levels(&levels);
+
+    // Asking for more than there are gives all of them.
+    for &(width, height) in &[(1, 1), (2, 3), (4, 2), (9, 6)] {
+        let expected = brute_regions(&levels, width, height);
+        let best = rack.best_regions(width, height, 100);
+        check_order(&best);
+        assert_eq!(best, expected, "{}x{}", width, height);
+        assert_eq!(rack.best_regions(width, height, 5), &expected[.. expected.len().min(5)]);
+    }
+
+    assert_eq!(rack.best_regions(10, 1, 5), vec![]);
+    assert_eq!(rack.best_regions(0, 3, 5), vec![]);
+    assert_eq!(rack.best_regions(3, 3, 0), vec![]);
+}
+
+#[test]
+fn best_squares() {
+    let levels = levels(7, 5);
+    let rack = Rack::from_levels(&levels);
+
+    let mut expected: Vec<Region> = (1 ..= 5).flat_map(|size| brute_regions(&levels, size, size)).collect();
+    expected.sort_by(|a, b| b.cmp(a));
+    let best = rack.best_squares(1 ..= 7, 1000);
+    check_order(&best);
+    assert_eq!(best, expected);
+    assert_eq!(rack.best_squares(1 ..= 7, 3), &expected[.. 3]);
+
+    // Limiting the sizes only looks at those.
+    let best = rack.best_squares(2 ..= 3, 1000);
+    check_order(&best);
+    assert!(best.iter().all(|r| r.width == r.height && (2 ..= 3).contains(&r.width)));
+    assert_eq!(best.len(), 6 * 4 + 5 * 3);
+}
+
+#[test]
+fn examples() {
+    let cases = [
+        (18, (33, 45, 29), (90, 269, 16, 113)),
+        (42, (21, 61, 30), (232, 251, 12, 119)),
+    ];
+    for &(serial, (x, y, power), (sx, sy, size, square)) in &cases {
+        let rack = Rack::new(serial, 300, 300);
+        let best = rack.best_regions(3, 3, 1);
+        assert_eq!(best, vec![Region { x, y, width: 3, height: 3, power }], "serial {}", serial);
+
+        let best = rack.best_squares(1 ..= 300, 1);
+        assert_eq!(best, vec![Region { x: sx, y: sy, width: size, height: size, power: square }],
+                   "serial {}", serial);
+    }
+}
+
+#[test]
+fn serial_from_input() {
+    let input = InputSource::Text("18\n".to_string());
+    assert_eq!(Day11.part1(&input).unwrap(), Answer::Coord(33, 45));
+
+    match Day11.part1(&InputSource::Text("eighteen\n".to_string())) {
+        Err(Error::Parse(err)) => assert_eq!((err.line, err.column), (1, 1)),
+        other => panic!("expected a parse error, got {:?}", other.map_err(|e| e.to_string())),
+    }
+    assert!(Day11.part1(&InputSource::Text(String::new())).is_err());
+}
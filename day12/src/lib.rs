@@ -0,0 +1,166 @@
+use std::{
+    collections::BTreeSet,
+};
+use common::{eof_error, Answer, InputSource, Result, Solver};
+
+pub struct Day12;
+
+impl Solver for Day12 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let mut st = State::from_input(input)?;
+        for _ in 0 .. 50 {
+            st.update();
+            // println!("{} {}", st.show(), st.sum());
+        }
+        // println!("{:?}", st);
+        Ok(st.sum().into())
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let mut st = State::from_input(input)?;
+
+        // By printing these out, we determine that by at least 1000, the values
+        // have stabilized, and will increment each time by the number of grown
+        // plants.  We can extrapolate from there to get the answer for 50
+        // billion, but we have to be careful to use 64-bit values.
+        for _ in 0 .. 1000 {
+            st.update();
+        }
+        let count = st.grown.len();
+        let base = st.sum();
+        // println!("count: {}, base: {}", count, base);
+        let result = (50_000_000_000u64 - 1000) * count as u64 + base as u64;
+
+        // This clearly isn't the intended way of computing this, as this will
+        // take decades to compute.
+        // The key is to realize that there is a pattern to the numbers, and we
+        // can just adjust the numbers for the larger count.
+        /*
+        for i in 20u64 .. 50000000000 {
+            if i % 1000000 == 0 {
+                println!("{}: {:?}", i, st);
+            }
+            st.update();
+        }
+        println!("{:?}", st.sum());
+        */
+        Ok(Answer::Int(result as i64))
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    grown: BTreeSet<i32>,
+    valids: u32,
+}
+
+impl State {
+    fn from_input(input: &InputSource) -> Result<State> {
+        let all_lines = input.lines("day12", "real.txt")?;
+        let eof = |message| eof_error(&input.display_name("day12", "real.txt"),
+                                      all_lines.len(), message);
+        let mut lines = all_lines.iter();
+
+        let initial = lines.next().ok_or_else(|| eof("expecting the initial state"))?;
+        if !initial.text.starts_with("initial state: ") {
+            return Err(initial.error_line("expecting \"initial state: \"").into());
+        }
+
+        let mut grown = BTreeSet::new();
+        for (num, ch) in initial.text.bytes().skip(15).enumerate() {
+            match ch {
+                b'#' => { grown.insert(num as i32); }
+                b'.' => (),
+                _ => return Err(initial.error(num + 15, "expecting '#' or '.'").into()),
+            }
+        }
+
+        // Skip a line.
+        let blank = lines.next().ok_or_else(|| eof("expecting a blank line"))?;
+        if !blank.text.is_empty() {
+            return Err(blank.error_line("expecting a blank line").into());
+        }
+
+        let mut valids = 0;
+
+        for line in lines {
+            let fields: Vec<_> = line.text.split(" => ").collect();
+
+            if fields.len() != 2 || fields[0].len() != 5 ||
+                !fields[0].bytes().all(|b| b == b'#' || b == b'.')
+            {
+                return Err(line.error_line("expecting a rule like \"..#.# => #\"").into());
+            }
+            match fields[1] {
+                "#" => (),
+                "." => continue,
+                _ => return Err(line.error(9, "expecting '#' or '.'").into()),
+            }
+            let mut value = 0;
+            for b in fields[0].bytes() {
+                value <<= 1;
+                if b == b'#' {
+                    value |= 1;
+                }
+            }
+            // println!("{:?}: 0b{:b}", fields, value);
+
+            valids |= 1 << value;
+        }
+
+        Ok(State {
+            grown,
+            valids,
+        })
+    }
+
+    /// Update according to the loaded rules.
+    fn update(&mut self) {
+        let mut new_grown = BTreeSet::new();
+
+        for p in self.full_iter() {
+            let mut value = 0;
+            for b in -2 ..= 2 {
+                value <<= 1;
+                if self.grown.contains(&(p + b)) {
+                    value |= 1;
+                }
+            }
+
+            if self.valids & (1 << value) != 0 {
+                new_grown.insert(p);
+            }
+            // println!("Check at: {:3}: {:05b} {}", p, value, self.valids & (1 << value) != 0);
+        }
+        // println!("{:?}", new_grown);
+        self.grown = new_grown;
+    }
+
+    fn sum(&self) -> i32 {
+        self.grown.iter().sum()
+    }
+
+    #[allow(dead_code)]
+    fn show(&self) -> String {
+        let mut result = String::new();
+        let left = self.grown.iter().next().cloned().unwrap_or(0);
+        {
+            use std::fmt::Write;
+            write!(&mut result, "{:6}: ", left).unwrap();
+        }
+        for p in self.full_iter() {
+            result.push(if self.grown.contains(&p) { '#' } else { '.' });
+        }
+        result
+    }
+
+    /// Return an iterator covering the range 2 beyond the maximum/minimum
+    /// elements set.  With no plants, this is empty.
+    fn full_iter(&self) -> impl Iterator<Item=i32> {
+        let (left, right) = match (self.grown.iter().next(), self.grown.iter().next_back()) {
+            (Some(&left), Some(&right)) => (left - 2, right + 2),
+            _ => (0, -1),
+        };
+        left ..= right
+    }
+}
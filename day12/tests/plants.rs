@@ -0,0 +1,22 @@
+use common::{Answer, InputSource, Solver};
+use day12::Day12;
+
+fn input(text: &str) -> InputSource {
+    InputSource::Text(text.to_string())
+}
+
+/// Once there are no plants, there is nothing left to grow from.
+#[test]
+fn no_plants() {
+    let cases = [
+        // None to start with.
+        "initial state: .....\n\n...## => #\n",
+        // They all die in the first generation.
+        "initial state: #..#.#\n\n",
+        "initial state: ##.##\n\n..#.. => .\n.##.. => .\n",
+    ];
+    for text in &cases {
+        assert_eq!(Day12.part1(&input(text)).unwrap(), Answer::from(0), "{:?}", text);
+        assert_eq!(Day12.part2(&input(text)).unwrap(), Answer::Int(0), "{:?}", text);
+    }
+}
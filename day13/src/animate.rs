@@ -0,0 +1,242 @@
+//! Watching the carts run, in the terminal.
+//!
+//! The track is redrawn after every tick, with each car in its own color,
+//! and crashes marked in red.  A map bigger than the terminal is shown
+//! through a viewport, which can be scrolled around.  The keys are:
+//!
+//! - space: pause or resume
+//! - `n`: run a single tick, and pause
+//! - `+` and `-`: run faster or slower
+//! - arrows or `hjkl`: scroll the viewport
+//! - `c`: scroll to the most recent crash
+//! - `q` or escape: quit
+
+use std::{
+    collections::HashMap,
+    fmt::Write as _,
+    io::{self, Write},
+    thread,
+    time::{Duration, Instant},
+};
+use termion::{
+    clear,
+    color::{self, Color},
+    cursor,
+    event::Key,
+    input::{Keys, TermRead},
+    raw::IntoRawMode,
+    screen::AlternateScreen,
+    style,
+    AsyncReader,
+};
+use common::{Error, Point, Result};
+use crate::{Event, Track};
+
+/// The colors the cars are drawn in, by id.
+const PALETTE: [&dyn Color; 6] = [
+    &color::LightGreen, &color::LightYellow, &color::LightBlue,
+    &color::LightMagenta, &color::LightCyan, &color::LightWhite,
+];
+
+/// Ticks per second, to start with, and the limits.
+const SPEED: u32 = 10;
+const MIN_SPEED: u32 = 1;
+const MAX_SPEED: u32 = 1000;
+
+/// How often to check for keys, while waiting for the next tick.
+const POLL: Duration = Duration::from_millis(10);
+
+/// The lines at the bottom of the screen used for the status.
+const STATUS_LINES: u16 = 2;
+
+/// Run the carts until at most one is left, drawing the track in the
+/// terminal after every tick.  After the last crash, the final state stays
+/// on the screen until the user quits.
+pub fn animate(track: Track) -> Result<()> {
+    if !termion::is_tty(&io::stdout()) {
+        return Err(Error::usage("--animate needs to run in a terminal"));
+    }
+
+    let out = io::stdout().into_raw_mode().map_err(|e| terminal_error("set up", e))?;
+    let out = AlternateScreen::from(cursor::HideCursor::from(out));
+    Animation::new(track, out).run(termion::async_stdin().keys())
+}
+
+/// A track being animated, and how it is being shown.  Each frame is
+/// drawn for a screen of a given size, in characters, with the last two
+/// lines holding the status.
+pub struct Animation<W: Write> {
+    track: Track,
+
+    /// Every crash so far, and the tick it happened in.
+    crashes: Vec<(Point, u64)>,
+
+    /// The point on the map shown at the top left of the screen.
+    view: Point,
+
+    speed: u32,
+    paused: bool,
+    out: W,
+}
+
+impl<W: Write> Animation<W> {
+    /// Start animating the track, with frames written to `out`.
+    pub fn new(track: Track, out: W) -> Animation<W> {
+        Animation {
+            track,
+            crashes: vec![],
+            view: Point::new(0, 0),
+            speed: SPEED,
+            paused: false,
+            out,
+        }
+    }
+
+    /// Where the frames are written.
+    pub fn output(&mut self) -> &mut W {
+        &mut self.out
+    }
+
+    fn run(&mut self, mut keys: Keys<AsyncReader>) -> Result<()> {
+        let mut last = Instant::now();
+        self.draw(terminal_size()?)?;
+        loop {
+            let mut step = false;
+            for key in keys.by_ref() {
+                let size = terminal_size()?;
+                match key.map_err(|e| terminal_error("read from", e))? {
+                    Key::Char('q') | Key::Esc | Key::Ctrl('c') => return Ok(()),
+                    Key::Char(' ') => self.paused = !self.paused,
+                    Key::Char('n') => {
+                        self.paused = true;
+                        step = true;
+                    }
+                    Key::Char('+') | Key::Char('=') => self.speed = (self.speed * 2).min(MAX_SPEED),
+                    Key::Char('-') => self.speed = (self.speed / 2).max(MIN_SPEED),
+                    Key::Up | Key::Char('k') => self.scroll(Point::UP, size),
+                    Key::Down | Key::Char('j') => self.scroll(Point::DOWN, size),
+                    Key::Left | Key::Char('h') => self.scroll(Point::LEFT, size),
+                    Key::Right | Key::Char('l') => self.scroll(Point::RIGHT, size),
+                    Key::Char('c') => {
+                        if let Some(&(pos, _)) = self.crashes.last() {
+                            self.center(pos, size);
+                        }
+                    }
+                    _ => (),
+                }
+                self.draw(size)?;
+            }
+
+            let due = !self.paused && last.elapsed() >= Duration::from_secs(1) / self.speed;
+            if (step || due) && !self.finished() {
+                last = Instant::now();
+                self.tick()?;
+                self.draw(terminal_size()?)?;
+            } else {
+                thread::sleep(POLL);
+            }
+        }
+    }
+
+    /// Run a single tick, remembering where any cars crashed.
+    pub fn tick(&mut self) -> Result<()> {
+        for event in self.track.tick()? {
+            if let Event::Collision { tick, pos, .. } = event {
+                self.crashes.push((pos, tick));
+            }
+        }
+        Ok(())
+    }
+
+    /// Has the race finished?
+    pub fn finished(&self) -> bool {
+        self.track.cars.len() <= 1
+    }
+
+    /// Move the viewport a quarter of a screen in the given direction.
+    fn scroll(&mut self, dir: Point, size: (u16, u16)) {
+        let (width, height) = map_area(size);
+        let by = Point::new(dir.x * (width / 4).max(1), dir.y * (height / 4).max(1));
+        self.view = self.clamp(self.view + by, size);
+    }
+
+    /// Move the viewport so the point is in the middle of the screen.
+    pub fn center(&mut self, pos: Point, size: (u16, u16)) {
+        let (width, height) = map_area(size);
+        self.view = self.clamp(pos - Point::new(width / 2, height / 2), size);
+    }
+
+    /// Keep the viewport from going past the edges of the map.
+    fn clamp(&self, view: Point, size: (u16, u16)) -> Point {
+        let (width, height) = map_area(size);
+        let max_x = (self.track.track.width() as i64 - width).max(0);
+        let max_y = (self.track.track.height() as i64 - height).max(0);
+        Point::new(view.x.max(0).min(max_x), view.y.max(0).min(max_y))
+    }
+
+    /// Draw a frame for a screen of the given size.
+    pub fn draw(&mut self, size: (u16, u16)) -> Result<()> {
+        let (width, height) = map_area(size);
+        self.view = self.clamp(self.view, size);
+        let tick = self.track.ticks;
+        let cars: HashMap<_, _> = self.track.cars.iter().map(|car| (car.pos, car)).collect();
+        let crashes: HashMap<_, _> = self.crashes.iter().cloned().collect();
+
+        // Build the whole frame before writing it, so it doesn't flicker.
+        let mut frame = format!("{}", clear::All);
+        for row in 0 .. height.min(self.track.track.height() as i64) {
+            write!(frame, "{}", cursor::Goto(1, row as u16 + 1)).unwrap();
+            for col in 0 .. width.min(self.track.track.width() as i64) {
+                let pos = self.view + Point::new(col, row);
+                let piece = self.track.track.get(pos).cloned().unwrap_or(b' ') as char;
+                if let Some(car) = cars.get(&pos) {
+                    write!(frame, "{}{}{}{}{}", style::Bold, color::Fg(PALETTE[car.id % PALETTE.len()]),
+                           car.dir.char_indicator(), color::Fg(color::Reset), style::Reset).unwrap();
+                } else if let Some(&when) = crashes.get(&pos) {
+                    if when == tick {
+                        // A crash that just happened stands out more.
+                        write!(frame, "{}{}X{}{}", color::Bg(color::Red), color::Fg(color::White),
+                               color::Fg(color::Reset), color::Bg(color::Reset)).unwrap();
+                    } else {
+                        write!(frame, "{}X{}", color::Fg(color::Red), color::Fg(color::Reset)).unwrap();
+                    }
+                } else {
+                    frame.push(piece);
+                }
+            }
+        }
+
+        let state = if self.finished() {
+            "finished"
+        } else if self.paused {
+            "paused"
+        } else {
+            "running"
+        };
+        write!(frame, "{}{}tick {}, {} cars, {} crashes, {} ticks/s, {}, viewing {},{}{}",
+               cursor::Goto(1, height as u16 + 1), style::Bold,
+               tick, self.track.cars.len(), self.crashes.len(), self.speed, state,
+               self.view.x, self.view.y, style::Reset).unwrap();
+        write!(frame, "{}space pause, n step, +/- speed, arrows scroll, c last crash, q quit",
+               cursor::Goto(1, height as u16 + 2)).unwrap();
+
+        self.out.write_all(frame.as_bytes())
+            .and_then(|_| self.out.flush())
+            .map_err(|e| terminal_error("draw on", e))
+    }
+}
+
+fn terminal_size() -> Result<(u16, u16)> {
+    termion::terminal_size().map_err(|e| terminal_error("get the size of", e))
+}
+
+/// An error using the terminal, which would otherwise be reported as one
+/// reading the input.
+fn terminal_error(doing: &str, source: io::Error) -> Error {
+    Error::io(format!("Unable to {} the terminal", doing), source)
+}
+
+/// The part of a screen of the given size available for the map.
+fn map_area((width, height): (u16, u16)) -> (i64, i64) {
+    (width as i64, height.saturating_sub(STATUS_LINES).max(1) as i64)
+}
@@ -0,0 +1,82 @@
+//! Run the mine carts, and write out everything that happens.
+//!
+//! Usage: `cartlog [--ticks N] [--output <file>] [--check <file>] [input]`.
+//! Runs until at most one car is left, or for N ticks, and writes each
+//! event as a line of JSON, to standard output if no output file is given.
+//! With `--check`, the events are instead compared against a log from an
+//! earlier run, or from another implementation, and the first difference
+//! is reported.
+
+use std::{
+    env,
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+use common::{Error, InputSource, Result};
+use day13::{Event, Track};
+
+const USAGE: &str = "Usage: cartlog [--ticks N] [--output <file>] [--check <file>] [input]";
+
+fn main() {
+    common::run(try_main);
+}
+
+fn try_main() -> Result<()> {
+    let mut ticks = None;
+    let mut output = None;
+    let mut check = None;
+    let mut input = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ticks" => {
+                let arg = args.next().ok_or_else(|| Error::usage(USAGE))?;
+                ticks = Some(arg.parse().map_err(|_| Error::usage(format!("Invalid number: {:?}", arg)))?);
+            }
+            "--output" => output = Some(args.next().ok_or_else(|| Error::usage(USAGE))?),
+            "--check" => check = Some(args.next().ok_or_else(|| Error::usage(USAGE))?),
+            _ if input.is_none() && (arg == "-" || !arg.starts_with("--")) => input = Some(arg),
+            _ => return Err(Error::usage(USAGE)),
+        }
+    }
+
+    let input = InputSource::from_arg(input.as_deref())?;
+    let mut track = Track::from_input(&input)?;
+    let events = track.run(ticks)?;
+
+    if let Some(path) = &check {
+        let expected = day13::read_events(&InputSource::File(path.into()))?;
+        return compare(&expected, &events);
+    }
+
+    match &output {
+        Some(path) => {
+            let file = File::create(path).map_err(|e| Error::io_path(path.as_ref(), e))?;
+            let mut out = BufWriter::new(file);
+            day13::write_events(&mut out, &events)
+                .and_then(|_| out.flush())
+                .map_err(|e| Error::io_write(path.as_ref(), e))?;
+        }
+        None => {
+            day13::write_events(&mut io::stdout().lock(), &events)
+                .map_err(|e| Error::io("Unable to write to standard output", e))?;
+        }
+    }
+    eprintln!("{} ticks, {} cars left", track.ticks(), track.car_count());
+    Ok(())
+}
+
+/// Check that the events are the same as the expected ones.
+fn compare(expected: &[Event], events: &[Event]) -> Result<()> {
+    for (index, (want, got)) in expected.iter().zip(events).enumerate() {
+        if want != got {
+            return Err(Error::solve(format!("Event {} differs: expected {:?}, got {:?}", index + 1, want, got)));
+        }
+    }
+    if expected.len() != events.len() {
+        return Err(Error::solve(format!("Expected {} events, got {}", expected.len(), events.len())));
+    }
+    println!("All {} events match", events.len());
+    Ok(())
+}
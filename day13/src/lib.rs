@@ -0,0 +1,388 @@
+//! Mine carts running around a track, from day 13.
+//!
+//! Each tick, the carts move one step in reading order, turning on curves,
+//! and at intersections turning left, going straight, and turning right in
+//! turn.  A cart that moves onto another crashes, and both are removed.
+//! Everything that happens is reported as an `Event`, and a run's events
+//! can be written out as JSON lines, to replay or compare later.  With the
+//! "animate" feature, which is on by default, the run can also be watched
+//! in the terminal, with `animate`.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::{self, Write},
+    result,
+};
+use common::{Answer, Error, Grid, InputSource, ParseError, Point, Result, Solver};
+
+#[cfg(feature = "animate")]
+mod animate;
+mod validate;
+
+#[cfg(feature = "animate")]
+pub use animate::{animate, Animation};
+
+pub struct Day13;
+
+/// The number of rows of the map shown above and below a problem.
+const CONTEXT: i64 = 2;
+
+impl Solver for Day13 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let mut tr = Track::from_input(input)?;
+        tr.sort_cars();
+        if tr.cars.len() < 2 {
+            return Err(Error::solve("Need at least two cars for a crash"));
+        }
+
+        loop {
+            if let Some(pos) = tr.tick()?.iter().find_map(Event::collision) {
+                return Ok(pos.into());
+            }
+        }
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let mut tr = Track::from_input(input)?;
+        tr.sort_cars();
+
+        // Continue running (with collisions) until there is only one car
+        // left.
+        while tr.cars.len() > 1 {
+            tr.tick()?;
+        }
+
+        // The final result is the position of the last car.
+        match tr.cars.first() {
+            Some(car) => Ok(car.pos.into()),
+            None => Err(Error::solve("No cars are left on the track")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Turn {
+    Left, Straight, Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Facing {
+    Up, Right, Down, Left,
+}
+
+/// Something that happened during a tick.  Ticks are numbered from 1.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum Event {
+    /// A tick has started, with this many cars still running.
+    Tick { tick: u64, cars: usize },
+
+    /// A car reached an intersection, and turned (or went straight),
+    /// leaving it facing a new direction.
+    Turn { tick: u64, car: usize, pos: Point, turn: Turn, facing: Facing },
+
+    /// A car ran into another.  `cars` is the one that moved, and then the
+    /// one it hit.  Both are removed from the track.
+    Collision { tick: u64, pos: Point, cars: [usize; 2] },
+}
+
+impl Event {
+    /// Where the collision was, if this is one.
+    pub fn collision(&self) -> Option<Point> {
+        match self {
+            Event::Collision { pos, .. } => Some(*pos),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Car {
+    id: usize,   // A unique id for each car, used for removal.
+    pos: Point,
+    turn: Turn,
+    dir: Facing,
+}
+
+/// The track, and the cars running on it.  Cars are numbered from 0, in
+/// reading order of where they start.
+pub struct Track {
+    /// The name of the input, for reporting errors.
+    file: String,
+    track: Grid<u8>,
+    cars: Vec<Car>,
+
+    /// The number of ticks run so far.
+    ticks: u64,
+}
+
+impl fmt::Debug for Track {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cars: HashMap<_, _> = self.cars.iter().map(|car| (car.pos, car)).collect();
+
+        writeln!(f, "track cars: {:?}", self.cars)?;
+        let rows = self.track.render(|p, &b| {
+            match cars.get(&p) {
+                None => b as char,
+                Some(car) => car.dir.char_indicator(),
+            }
+        });
+        for row in rows {
+            writeln!(f, "   {}", row)?;
+        }
+        Ok(())
+    }
+}
+
+impl Track {
+    pub fn from_input(input: &InputSource) -> Result<Track> {
+        let lines = input.lines("day13", "tracks.txt")?;
+
+        let mut cars = vec![];
+
+        // Fix up the cars, replacing the track segments, and separately
+        // recording the position of the cars.
+        let track = Grid::from_lines(&lines, b' ', |pos, ch| {
+            let id = cars.len();
+            match ch {
+                '^' => {
+                    cars.push(Car{pos, turn: Turn::Left, dir: Facing::Up, id});
+                    Some(b'|')
+                }
+                'v' => {
+                    cars.push(Car{pos, turn: Turn::Left, dir: Facing::Down, id});
+                    Some(b'|')
+                }
+                '<' => {
+                    cars.push(Car{pos, turn: Turn::Left, dir: Facing::Left, id});
+                    Some(b'-')
+                }
+                '>' => {
+                    cars.push(Car{pos, turn: Turn::Left, dir: Facing::Right, id});
+                    Some(b'-')
+                }
+                ' ' | '-' | '|' | '/' | '\\' | '+' => Some(ch as u8),
+                _ => None,
+            }
+        })?;
+
+        let track = Track{
+            file: input.display_name("day13", "tracks.txt"),
+            track,
+            cars,
+            ticks: 0,
+        };
+        track.validate()?;
+        Ok(track)
+    }
+
+    /// The number of cars still running.
+    pub fn car_count(&self) -> usize {
+        self.cars.len()
+    }
+
+    /// The number of ticks run so far.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    // Sort the cars, so that the y coordinate is first, then the x.
+    fn sort_cars(&mut self) {
+        self.cars.sort_by_key(|c| c.pos);
+    }
+
+    /// Take the given Car, and return a new Car adjusted for the movement
+    /// (and possible direction change).  A turn at an intersection is added
+    /// to the events.
+    fn move_car(&self, car: &Car, events: &mut Vec<Event>) -> result::Result<Car, ParseError> {
+        // Figure out the new position of this particular car.
+        let pos = car.pos + car.dir.step();
+
+        let piece = self.track.get(pos).cloned();
+        let (new_dir, new_turn) = match piece.unwrap_or(b' ') {
+            b'/' => (match car.dir {
+                Facing::Up => Facing::Right,
+                Facing::Right => Facing::Up,
+                Facing::Down => Facing::Left,
+                Facing::Left => Facing::Down,
+            }, car.turn),
+            b'\\' => (match car.dir {
+                Facing::Up => Facing::Left,
+                Facing::Left => Facing::Up,
+                Facing::Right => Facing::Down,
+                Facing::Down => Facing::Right,
+            }, car.turn),
+            b'+' => {
+                let dir = car.dir.apply_turn(car.turn);
+                events.push(Event::Turn { tick: self.ticks, car: car.id, pos, turn: car.turn, facing: dir });
+                (dir, car.turn.next_turn())
+            }
+            b'-' | b'|' => (car.dir, car.turn),
+            _ => return Err(self.error(car.pos, "car moves off of the track")),
+        };
+
+        Ok(Car {
+            pos,
+            dir: new_dir,
+            turn: new_turn,
+            id: car.id,
+        })
+    }
+
+    /// Construct an error pointing at the given location on the map, with
+    /// a few rows of the map around it.  The rows show the cars, as the
+    /// input does.
+    fn error(&self, pos: Point, message: &str) -> ParseError {
+        let row = |y: i64| {
+            let row = self.track.row(y).unwrap_or(&[]);
+            let text: String = row.iter().enumerate().map(|(x, &b)| {
+                match self.cars.iter().find(|car| car.pos == Point::new(x as i64, y)) {
+                    Some(car) => car.dir.char_indicator(),
+                    None => b as char,
+                }
+            }).collect();
+            text.trim_end().to_string()
+        };
+        let context = (pos.y - CONTEXT ..= pos.y + CONTEXT)
+            .filter(|&y| y != pos.y && y >= 0 && y < self.track.height() as i64)
+            .map(|y| (y as usize + 1, row(y)))
+            .collect();
+        ParseError::new(&self.file, pos.y as usize + 1, pos.x as usize + 1, &row(pos.y), message)
+            .with_context(context)
+    }
+
+    /// Run a single tick, returning everything that happened.  Note that
+    /// when there is a collision, the two affected cars will be removed.
+    pub fn tick(&mut self) -> Result<Vec<Event>> {
+        let mut places: HashMap<_, _> = self.cars.iter().map(|c| (c.pos, c.id)).collect();
+        self.sort_cars();
+        self.ticks += 1;
+
+        let mut events = vec![Event::Tick { tick: self.ticks, cars: self.cars.len() }];
+        let mut removes: HashSet<usize> = HashSet::new();
+
+        let mut new_cars = Vec::with_capacity(self.cars.len());
+        for car in &self.cars {
+            if removes.contains(&car.id) {
+                continue;
+            }
+            let new_car = self.move_car(car, &mut events)?;
+            match places.get(&new_car.pos) {
+                None => (),
+                Some(ccar) => {
+                    events.push(Event::Collision {
+                        tick: self.ticks,
+                        pos: new_car.pos,
+                        cars: [new_car.id, *ccar],
+                    });
+
+                    // And mark both cars as being removed.
+                    removes.insert(*ccar);
+                    removes.insert(new_car.id);
+
+                    // Since both cars are now removed, neither is left
+                    // at either place for a later car to run into.
+                    places.remove(&new_car.pos);
+                    places.remove(&car.pos);
+                    continue;
+                },
+            }
+
+            places.remove(&car.pos);
+            places.insert(new_car.pos, new_car.id);
+
+            new_cars.push(new_car);
+        }
+
+        // Update the removes, since it is possible for cars to go away
+        // because a lower car collides with it.
+        self.cars = new_cars.into_iter().filter(|c| !removes.contains(&c.id)).collect();
+        Ok(events)
+    }
+
+    /// Run until at most one car is left, or the given number of ticks
+    /// have run, returning all of the events.
+    pub fn run(&mut self, limit: Option<u64>) -> Result<Vec<Event>> {
+        let mut events = vec![];
+        while self.cars.len() > 1 && limit.is_none_or(|limit| self.ticks < limit) {
+            events.extend(self.tick()?);
+        }
+        Ok(events)
+    }
+}
+
+/// Write the events out, one JSON object per line.
+pub fn write_events<W: Write>(out: &mut W, events: &[Event]) -> io::Result<()> {
+    for event in events {
+        serde_json::to_writer(&mut *out, event)?;
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Read events written by `write_events`.  Blank lines are skipped.
+pub fn read_events(input: &InputSource) -> Result<Vec<Event>> {
+    let mut events = vec![];
+    for line in input.lines("day13", "events.jsonl")? {
+        if line.text.trim().is_empty() {
+            continue;
+        }
+        let event = serde_json::from_str(&line.text)
+            .map_err(|e| line.error(e.column().saturating_sub(1), format!("invalid event: {}", e)))?;
+        events.push(event);
+    }
+    Ok(events)
+}
+
+impl Facing {
+    /// The offset to move one step in this direction.
+    fn step(&self) -> Point {
+        match self {
+            Facing::Up => Point::UP,
+            Facing::Right => Point::RIGHT,
+            Facing::Down => Point::DOWN,
+            Facing::Left => Point::LEFT,
+        }
+    }
+
+    fn apply_turn(&self, turn: Turn) -> Facing {
+        match turn {
+            Turn::Left => match *self {
+                Facing::Up => Facing::Left,
+                Facing::Right => Facing::Up,
+                Facing::Down => Facing::Right,
+                Facing::Left => Facing::Down,
+            },
+            Turn::Straight => *self,
+            Turn::Right => match *self {
+                Facing::Up => Facing::Right,
+                Facing::Right => Facing::Down,
+                Facing::Down => Facing::Left,
+                Facing::Left => Facing::Up,
+            }
+        }
+    }
+
+    fn char_indicator(&self) -> char {
+        match *self {
+            Facing::Up => '^',
+            Facing::Right => '>',
+            Facing::Down => 'v',
+            Facing::Left => '<',
+        }
+    }
+}
+
+impl Turn {
+    fn next_turn(&self) -> Turn {
+        match *self {
+            Turn::Left => Turn::Straight,
+            Turn::Straight => Turn::Right,
+            Turn::Right => Turn::Left,
+        }
+    }
+}
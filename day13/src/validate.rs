@@ -0,0 +1,152 @@
+//! Checking that a track makes sense, before running any carts on it.
+//!
+//! Every piece of track has arms leaving from some of its sides: straight
+//! pieces have two opposite arms, intersections have all four, and curves
+//! have two adjacent arms, which way round depending on the track next to
+//! them.  The curves are worked out first, and then each arm has to lead
+//! to a neighbor with an arm coming back.  Carts start on straight track,
+//! so the track under each one has to join up the same way.
+
+use std::{
+    collections::BTreeMap,
+    result,
+};
+use common::{ParseError, Point};
+use crate::{Facing, Track};
+
+const SIDES: [Facing; 4] = [Facing::Up, Facing::Right, Facing::Down, Facing::Left];
+
+/// The ways a curve can be joined up: `/` is either the top left or the
+/// bottom right corner of a loop, and `\` the top right or bottom left.
+const SLASH: [[Facing; 2]; 2] = [[Facing::Right, Facing::Down], [Facing::Up, Facing::Left]];
+const BACKSLASH: [[Facing; 2]; 2] = [[Facing::Left, Facing::Down], [Facing::Up, Facing::Right]];
+
+/// The ways each curve could still be joined up.  Once the curves are
+/// resolved, each has just one.
+type Curves = BTreeMap<Point, Vec<[Facing; 2]>>;
+
+impl Track {
+    /// Check the track, returning an error for the first problem found.
+    /// Curves that can't be joined up either way are reported first, then
+    /// cars, and then everything else, in reading order.
+    pub(crate) fn validate(&self) -> result::Result<(), ParseError> {
+        let curves = self.resolve_curves()?;
+
+        for car in &self.cars {
+            if let Some(problem) = self.arm_problem(&curves, car.pos, &self.arms(&curves, car.pos)) {
+                return Err(self.error(car.pos, &format!(
+                    "car {} at {},{} has to start on track that joins up, but the track under it {}",
+                    car.dir.char_indicator(), car.pos.x, car.pos.y, problem)));
+            }
+        }
+
+        for (pos, &piece) in self.track.iter() {
+            let problem = match self.arm_problem(&curves, pos, &self.arms(&curves, pos)) {
+                None => continue,
+                Some(problem) => problem,
+            };
+            let message = match piece {
+                b'+' => format!("intersection at {},{} {}, it needs four arms", pos.x, pos.y, problem),
+                b'/' | b'\\' => format!("curve at {},{} {}", pos.x, pos.y, problem),
+                _ => format!("track at {},{} {}", pos.x, pos.y, problem),
+            };
+            return Err(self.error(pos, &message));
+        }
+        Ok(())
+    }
+
+    /// Work out which way round each curve goes, from the track around
+    /// it.  A curve is narrowed down to the ways that its neighbors could
+    /// join, until nothing changes.  If a curve could still go either way,
+    /// the first is picked, and the others are narrowed down again.
+    fn resolve_curves(&self) -> result::Result<Curves, ParseError> {
+        let mut curves: Curves = self.track.iter().filter_map(|(pos, &piece)| match piece {
+            b'/' => Some((pos, SLASH.to_vec())),
+            b'\\' => Some((pos, BACKSLASH.to_vec())),
+            _ => None,
+        }).collect();
+
+        loop {
+            let mut changed = true;
+            while changed {
+                changed = false;
+                let points: Vec<Point> = curves.keys().cloned().collect();
+                for pos in points {
+                    let ways: Vec<[Facing; 2]> = curves[&pos].iter()
+                        .filter(|way| self.arm_problem(&curves, pos, &way[..]).is_none())
+                        .cloned()
+                        .collect();
+                    if ways.is_empty() {
+                        return Err(self.error(pos, &format!(
+                            "curve at {},{} doesn't join up with track on two adjacent sides", pos.x, pos.y)));
+                    }
+                    if ways.len() < curves[&pos].len() {
+                        curves.insert(pos, ways);
+                        changed = true;
+                    }
+                }
+            }
+
+            match curves.values_mut().find(|ways| ways.len() > 1) {
+                Some(ways) => ways.truncate(1),
+                None => return Ok(curves),
+            }
+        }
+    }
+
+    /// The piece of track at the given position, or a space if it's off
+    /// the map.
+    fn piece(&self, pos: Point) -> u8 {
+        self.track.get(pos).cloned().unwrap_or(b' ')
+    }
+
+    /// The sides the piece at the given position has arms on.  A curve that
+    /// isn't resolved yet could have an arm on any side it might use.
+    fn arms(&self, curves: &Curves, pos: Point) -> Vec<Facing> {
+        match self.piece(pos) {
+            b'-' => vec![Facing::Left, Facing::Right],
+            b'|' => vec![Facing::Up, Facing::Down],
+            b'+' => SIDES.to_vec(),
+            b'/' | b'\\' => SIDES.iter().cloned()
+                .filter(|side| curves[&pos].iter().any(|way| way.contains(side)))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Describe the first arm from the given position that doesn't lead
+    /// to track that can join it, if there is one.
+    fn arm_problem(&self, curves: &Curves, pos: Point, arms: &[Facing]) -> Option<String> {
+        arms.iter().find_map(|&side| {
+            let next = pos + side.step();
+            if !self.track.bounds().contains(next) {
+                Some(format!("runs off the edge of the map {}", side.describe()))
+            } else if !self.arms(curves, next).contains(&side.reverse()) {
+                Some(format!("has no track joining it {}", side.describe()))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl Facing {
+    fn reverse(self) -> Facing {
+        match self {
+            Facing::Up => Facing::Down,
+            Facing::Right => Facing::Left,
+            Facing::Down => Facing::Up,
+            Facing::Left => Facing::Right,
+        }
+    }
+
+    /// Where this side is, for messages.
+    fn describe(self) -> &'static str {
+        match self {
+            Facing::Up => "above",
+            Facing::Right => "on the right",
+            Facing::Down => "below",
+            Facing::Left => "on the left",
+        }
+    }
+}
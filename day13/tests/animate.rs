@@ -0,0 +1,102 @@
+#![cfg(feature = "animate")]
+
+use termion::color;
+use common::{InputSource, Point};
+use day13::{Animation, Track};
+
+/// Two cars that crash into each other on their third tick, at 3,0.
+const MAP: &str = r"/>---<\
+|     |
+\-----/
+";
+
+fn animation() -> Animation<Vec<u8>> {
+    let track = Track::from_input(&InputSource::Text(MAP.to_string())).unwrap();
+    Animation::new(track, vec![])
+}
+
+/// Play the frame written so far onto a screen of the given size,
+/// following the cursor movements, and ignoring colors and styles.
+fn screen(anim: &mut Animation<Vec<u8>>, width: usize, height: usize) -> Vec<String> {
+    let frame = String::from_utf8(std::mem::take(anim.output())).unwrap();
+    let mut screen = vec![vec![' '; width]; height];
+    let (mut row, mut col) = (0, 0);
+    let mut chars = frame.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '\x1b' {
+            screen[row][col] = ch;
+            col += 1;
+            continue;
+        }
+        assert_eq!(chars.next(), Some('['));
+        let mut args = String::new();
+        for ch in chars.by_ref() {
+            if ch.is_ascii_alphabetic() {
+                if ch == 'H' {
+                    let mut nums = args.split(';').map(|n| n.parse::<usize>().unwrap());
+                    row = nums.next().unwrap() - 1;
+                    col = nums.next().unwrap() - 1;
+                }
+                break;
+            }
+            args.push(ch);
+        }
+    }
+    screen.into_iter().map(|row| row.into_iter().collect::<String>().trim_end().to_string()).collect()
+}
+
+#[test]
+fn frames() {
+    let mut anim = animation();
+    anim.draw((10, 5)).unwrap();
+    assert_eq!(screen(&mut anim, 80, 5), vec![
+        "/>---<\\",
+        "|     |",
+        "\\-----/",
+        "tick 0, 2 cars, 0 crashes, 10 ticks/s, running, viewing 0,0",
+        "space pause, n step, +/- speed, arrows scroll, c last crash, q quit",
+    ]);
+
+    anim.tick().unwrap();
+    anim.draw((10, 5)).unwrap();
+    assert_eq!(screen(&mut anim, 80, 5)[0], "/->-<-\\");
+
+    // The crash is marked, with a background, since it just happened.
+    anim.tick().unwrap();
+    anim.draw((10, 5)).unwrap();
+    let raw = String::from_utf8(anim.output().clone()).unwrap();
+    let just_crashed = color::Bg(color::Red).to_string();
+    assert!(raw.contains(&just_crashed), "{:?}", raw);
+    let lines = screen(&mut anim, 80, 5);
+    assert_eq!(lines[0], "/--X--\\");
+    assert_eq!(lines[3], "tick 2, 0 cars, 1 crashes, 10 ticks/s, finished, viewing 0,0");
+    assert!(anim.finished());
+
+    // Later, it's just marked in red.
+    anim.tick().unwrap();
+    anim.draw((10, 5)).unwrap();
+    let raw = String::from_utf8(anim.output().clone()).unwrap();
+    assert!(!raw.contains(&just_crashed));
+    assert!(raw.contains(&format!("{}X", color::Fg(color::Red))), "{:?}", raw);
+}
+
+/// A screen smaller than the map shows part of it, and the viewport can't
+/// be moved past the edges.
+#[test]
+fn viewport() {
+    let mut anim = animation();
+    anim.draw((4, 4)).unwrap();
+    let lines = screen(&mut anim, 80, 4);
+    assert_eq!(&lines[.. 2], &["/>--", "|"]);
+    assert_eq!(lines[2], "tick 0, 2 cars, 0 crashes, 10 ticks/s, running, viewing 0,0");
+
+    anim.center(Point::new(6, 2), (4, 4));
+    anim.draw((4, 4)).unwrap();
+    let lines = screen(&mut anim, 80, 4);
+    assert_eq!(&lines[.. 2], &["   |", "---/"]);
+    assert!(lines[2].ends_with("viewing 3,1"), "{}", lines[2]);
+
+    anim.center(Point::new(-10, -10), (4, 4));
+    anim.draw((4, 4)).unwrap();
+    assert!(screen(&mut anim, 80, 4)[2].ends_with("viewing 0,0"));
+}
@@ -0,0 +1,89 @@
+use common::{Answer, InputSource, Point, Solver};
+use day13::{Day13, Event, Track};
+
+/// The example from the second part of the puzzle, where several pairs of
+/// cars crash in the same tick.
+const EXAMPLE: &str = r"/>-<\
+|   |
+| /<+-\
+| | | v
+\>+</ |
+  |   ^
+  \<->/
+";
+
+fn input() -> InputSource {
+    InputSource::Text(EXAMPLE.to_string())
+}
+
+#[test]
+fn every_collision_is_reported() {
+    let mut track = Track::from_input(&input()).unwrap();
+    assert_eq!(track.car_count(), 9);
+
+    let first = track.tick().unwrap();
+    assert_eq!(first[0], Event::Tick { tick: 1, cars: 9 });
+    let crashes: Vec<_> = first.iter().filter_map(Event::collision).collect();
+    assert_eq!(crashes, vec![Point::new(2, 0), Point::new(2, 4), Point::new(6, 4)]);
+    assert_eq!(track.car_count(), 3);
+
+    let rest = track.run(None).unwrap();
+    let all: Vec<_> = first.iter().chain(&rest).collect();
+    assert_eq!(all.iter().filter(|e| e.collision().is_some()).count(), 4);
+    assert_eq!(track.car_count(), 1);
+    assert_eq!(Day13.part2(&input()).unwrap(), Answer::from(Point::new(6, 4)));
+
+    // No car is involved in more than one collision.
+    let mut crashed = vec![];
+    for event in all {
+        if let Event::Collision { cars, .. } = event {
+            crashed.extend_from_slice(cars);
+        }
+    }
+    crashed.sort();
+    crashed.dedup();
+    assert_eq!(crashed.len(), 8);
+}
+
+#[test]
+fn events_round_trip_as_json() {
+    let events = Track::from_input(&input()).unwrap().run(None).unwrap();
+    assert!(events.iter().any(|e| matches!(e, Event::Turn { .. })));
+
+    let mut text = vec![];
+    day13::write_events(&mut text, &events).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert_eq!(text.lines().count(), events.len());
+    assert!(text.starts_with("{\"event\":\"tick\",\"tick\":1,\"cars\":9}\n"));
+
+    let read = day13::read_events(&InputSource::Text(text)).unwrap();
+    assert_eq!(read, events);
+
+    let err = day13::read_events(&InputSource::Text("{\"event\":\"jump\"}\n".to_string())).unwrap_err();
+    assert!(err.to_string().contains("invalid event"), "{}", err);
+}
+
+/// A third car moving onto a crash site in the same tick doesn't hit the
+/// cars that were already removed.
+#[test]
+fn crash_site_is_cleared() {
+    let map = "  /-\\\n  | |\n/>+<+-\\\n| ^ | |\n\\-+-+-/\n  | |\n  \\-/\n";
+    let input = InputSource::Text(map.to_string());
+    let mut track = Track::from_input(&input).unwrap();
+    let events = track.run(None).unwrap();
+
+    let collisions: Vec<[usize; 2]> = events.iter().filter_map(|e| match e {
+        Event::Collision { cars, .. } => Some(*cars),
+        _ => None,
+    }).collect();
+    assert_eq!(collisions, vec![[1, 0]]);
+
+    let mut ids: Vec<usize> = collisions.iter().flatten().cloned().collect();
+    let count = ids.len();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(ids.len(), count, "a car is in two collisions");
+
+    assert_eq!(track.car_count(), 1);
+    assert_eq!(Day13.part2(&input).unwrap(), Answer::from(Point::new(2, 2)));
+}
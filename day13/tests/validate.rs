@@ -0,0 +1,78 @@
+use common::{Error, InputSource, ParseError};
+use day13::Track;
+
+/// Load the map, and return the error it should have.
+fn error(map: &str) -> ParseError {
+    match Track::from_input(&InputSource::Text(map.to_string())) {
+        Err(Error::Parse(err)) => err,
+        Err(err) => panic!("expected a parse error, got {:?}", err),
+        Ok(_) => panic!("expected an error for:\n{}", map),
+    }
+}
+
+#[test]
+fn good_tracks() {
+    for map in &["/->-\\\n|   |\n\\---/\n", "  /-\\\n  | |\n/-+-/\n| v\n\\-/\n"] {
+        assert!(Track::from_input(&InputSource::Text(map.to_string())).is_ok(), "{}", map);
+    }
+}
+
+#[test]
+fn rail_off_the_map() {
+    let err = error(" /-\\\n | |\n-+-+-\n | |\n \\-/\n");
+    assert_eq!(err.message, "track at 0,2 runs off the edge of the map on the left");
+    assert_eq!((err.line, err.column), (3, 1));
+    assert_eq!(err.text, "-+-+-");
+
+    // Two rows either side are shown.
+    let nums: Vec<usize> = err.context.iter().map(|&(num, _)| num).collect();
+    assert_eq!(nums, vec![1, 2, 4, 5]);
+    assert_eq!(err.to_string(), "\
+error: track at 0,2 runs off the edge of the map on the left
+ --> <stdin>:3:1
+  |
+1 |  /-\\
+2 |  | |
+3 | -+-+-
+  | ^
+4 |  | |
+5 |  \\-/");
+}
+
+#[test]
+fn disconnected_track() {
+    let err = error("/-\\\n| |\n|-+\n| |\n\\-/\n");
+    assert_eq!(err.message, "track at 1,2 has no track joining it on the left");
+}
+
+#[test]
+fn car_on_a_curve() {
+    let err = error(">-\\\n| |\n\\-/\n");
+    assert_eq!(err.message, "car > at 0,0 has to start on track that joins up, \
+                             but the track under it runs off the edge of the map on the left");
+    // The map is shown with the car, as it was written.
+    assert_eq!(err.text, ">-\\");
+}
+
+#[test]
+fn intersection_missing_an_arm() {
+    let err = error("\n/-+-\\\n| | |\n\\-+-/\n");
+    assert_eq!(err.message, "intersection at 2,1 has no track joining it above, it needs four arms");
+    assert_eq!((err.line, err.column), (2, 3));
+}
+
+#[test]
+fn unjoined_curve() {
+    let err = error("/-\\\n| |\n\\-\\\n");
+    assert_eq!(err.message, "curve at 2,2 doesn't join up with track on two adjacent sides");
+}
+
+/// A curve can only be one corner of a loop, so track that fits the
+/// other corner doesn't join it.
+#[test]
+fn curve_facing_away() {
+    let err = error("/-\\\n| v\n^ /-\\\n| | |\n\\-/-/\n");
+    assert_eq!(err.message, "car v at 2,1 has to start on track that joins up, \
+                             but the track under it has no track joining it below");
+    assert_eq!((err.line, err.column), (2, 3));
+}
@@ -0,0 +1,87 @@
+use std::result;
+use common::{eof_error, Answer, InputSource, Line, ParseError, Result, Solver};
+
+pub struct Day14;
+
+impl Solver for Day14 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let line = get_input(input)?;
+        let s1 = solve1(line.parse_at(0, &line.text)?);
+        Ok(Answer::Text(s1.iter().map(|ch| ch.to_string()).collect()))
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        // The same input is now a sequence of scores to look for.
+        let line = get_input(input)?;
+        Ok(solve2(&digits(&line)?).into())
+    }
+}
+
+/// Read the line of the input holding the puzzle's number.
+fn get_input(input: &InputSource) -> Result<Line> {
+    let lines = input.lines("day14", "recipes.txt")?;
+    match lines.into_iter().next() {
+        Some(line) if line.text.is_empty() => Err(line.error_line("expecting a number").into()),
+        Some(line) => Ok(line),
+        None => Err(eof_error(&input.display_name("day14", "recipes.txt"), 0,
+                              "expecting a number").into()),
+    }
+}
+
+/// The digits of the line, as scores.
+fn digits(line: &Line) -> result::Result<Vec<u8>, ParseError> {
+    line.text.bytes().enumerate().map(|(i, b)| match b {
+        b'0' ..= b'9' => Ok(b - b'0'),
+        _ => Err(line.error(i, "expecting a digit")),
+    }).collect()
+}
+
+fn solve1(limit: usize) -> Vec<u8> {
+    let mut recip = vec![3u8, 7];
+    let mut a = 0;
+    let mut b = 1;
+
+    loop {
+        let ascore = recip[a] as usize;
+        let bscore = recip[b] as usize;
+        let sum = ascore + bscore;
+        if sum > 9 {
+            recip.push((sum / 10) as u8);
+        }
+        recip.push((sum % 10) as u8);
+
+        a = (a + ascore + 1) % recip.len();
+        b = (b + bscore + 1) % recip.len();
+
+        if recip.len() >= limit + 10 {
+            break;
+        }
+    }
+
+    recip[limit .. limit + 10].to_vec()
+}
+
+fn solve2(pattern: &[u8]) -> usize {
+    let mut recip = vec![3u8, 7];
+    let mut a = 0;
+    let mut b = 1;
+
+    loop {
+        let ascore = recip[a] as usize;
+        let bscore = recip[b] as usize;
+        let sum = ascore + bscore;
+        if sum > 9 {
+            recip.push((sum / 10) as u8);
+            if recip.ends_with(pattern) {
+                return recip.len() - pattern.len();
+            }
+        }
+        recip.push((sum % 10) as u8);
+        if recip.ends_with(pattern) {
+            return recip.len() - pattern.len();
+        }
+
+        a = (a + ascore + 1) % recip.len();
+        b = (b + bscore + 1) % recip.len();
+    }
+}
@@ -0,0 +1,47 @@
+use common::{Answer, Error, InputSource, Solver};
+use day14::Day14;
+
+fn input(text: &str) -> InputSource {
+    InputSource::Text(text.to_string())
+}
+
+#[test]
+fn examples() {
+    let scores = [
+        ("9", "5158916779"),
+        ("5", "0124515891"),
+        ("18", "9251071085"),
+        ("2018", "5941429882"),
+    ];
+    for &(text, answer) in &scores {
+        assert_eq!(Day14.part1(&input(text)).unwrap(), Answer::Text(answer.to_string()), "{:?}", text);
+    }
+
+    let positions = [
+        ("51589", 9),
+        ("01245", 5),
+        ("92510", 18),
+        ("59414", 2018),
+    ];
+    for &(text, answer) in &positions {
+        assert_eq!(Day14.part2(&input(text)).unwrap(), Answer::from(answer), "{:?}", text);
+    }
+}
+
+#[test]
+fn malformed_input() {
+    let cases = [
+        ("", 1, 1, "expecting a number"),
+        ("\n", 1, 1, "expecting a number"),
+        ("91x9\n", 1, 3, "expecting a digit"),
+    ];
+    for &(text, line, column, message) in &cases {
+        match Day14.part2(&input(text)) {
+            Err(Error::Parse(err)) => {
+                assert_eq!((err.line, err.column, err.message.as_str()), (line, column, message), "{:?}", text);
+            }
+            other => panic!("{:?}: expected a parse error, got {:?}", text, other.map_err(|e| e.to_string())),
+        }
+    }
+    assert!(Day14.part1(&input("91x9\n")).is_err());
+}
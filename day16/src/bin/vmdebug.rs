@@ -0,0 +1,221 @@
+//! An interactive debugger for device programs.
+//!
+//! Usage: `vmdebug <input>` debugs the program in a day 16 style input,
+//! using the opcode numbers worked out from its samples.  `vmdebug --asm
+//! <file>` debugs a program written in assembly.  `--overflow <policy>`
+//! chooses what arithmetic does when it overflows, and can also be changed
+//! at the prompt.  Type "help" at the prompt for the commands.
+
+use std::{
+    env,
+    io::{self, BufRead, Write},
+};
+use common::{Error, Result};
+use vm::{Breakpoint, Debugger, Machine, Opcode, Stop, Word};
+
+const USAGE: &str = "Usage: vmdebug [--asm] [--overflow <policy>] <file>";
+
+const HELP: &str = "\
+Commands:
+  s, step [N]        run N instructions (default 1)
+  c, continue        run until a breakpoint, a watched register changes, or the end
+  rs, back [N]       step backward N instructions (default 1)
+  b, break ADDR|OP   stop before the instruction at ADDR, or any with opcode OP
+  d, delete ADDR|OP  remove a breakpoint
+  w, watch rN        stop when register N changes
+  u, unwatch rN      stop watching register N
+  r, regs            show the registers
+  l, list [N]        show N instructions around the instruction pointer
+  set rN VALUE       change a register
+  overflow POLICY    wrapping, checked, saturating, or widened arithmetic
+  info               show breakpoints, watches, and the overflow policy
+  q, quit            leave the debugger
+An empty line repeats the last command.";
+
+fn main() {
+    common::run(try_main);
+}
+
+fn try_main() -> Result<()> {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mut overflow = None;
+    if let Some(pos) = args.iter().position(|a| a == "--overflow") {
+        if pos + 1 >= args.len() {
+            return Err(Error::usage(USAGE));
+        }
+        overflow = Some(day16::parse_overflow(&args[pos + 1])?);
+        args.drain(pos ..= pos + 1);
+    }
+    let (program, mut machine) = match &args[..] {
+        [flag, path] if flag == "--asm" => day16::load_tool_program(path, true)?,
+        [path] => day16::load_tool_program(path, false)?,
+        _ => return Err(Error::usage(USAGE)),
+    };
+    if let Some(overflow) = overflow {
+        machine.set_overflow(overflow);
+    }
+
+    let mut dbg = Debugger::new(program, machine);
+    show_location(&dbg);
+
+    let stdin = io::stdin();
+    let mut last = String::new();
+    loop {
+        print!("(vmdebug) ");
+        io::stdout().flush().map_err(|e| Error::io("Unable to write to standard output", e))?;
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            println!();
+            break;
+        }
+        let line = line.trim();
+        let line = if line.is_empty() { last.clone() } else { line.to_string() };
+        last = line.clone();
+
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match command(&mut dbg, &words) {
+            Ok(true) => (),
+            Ok(false) => break,
+            Err(e) => println!("{}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Run a single command.  Returns false to quit.
+fn command(dbg: &mut Debugger, words: &[&str]) -> Result<bool> {
+    match words {
+        [] => (),
+        ["s"] | ["step"] => run_steps(dbg, 1)?,
+        ["s", n] | ["step", n] => run_steps(dbg, number(n)?)?,
+        ["c"] | ["continue"] => {
+            let stop = dbg.cont()?;
+            show_stop(dbg, stop);
+        }
+        ["rs"] | ["back"] => back(dbg, 1),
+        ["rs", n] | ["back", n] => back(dbg, number(n)?),
+        ["b", bp] | ["break", bp] => {
+            let bp = breakpoint(bp)?;
+            dbg.add_breakpoint(bp);
+            println!("Breakpoint at {}", bp);
+        }
+        ["d", bp] | ["delete", bp] => {
+            let bp = breakpoint(bp)?;
+            if !dbg.remove_breakpoint(bp) {
+                println!("No breakpoint at {}", bp);
+            }
+        }
+        ["w", reg] | ["watch", reg] => dbg.watch(register(reg)?)?,
+        ["u", reg] | ["unwatch", reg] => {
+            if !dbg.unwatch(register(reg)?) {
+                println!("{} is not being watched", reg);
+            }
+        }
+        ["r"] | ["regs"] => show_registers(dbg.machine()),
+        ["l"] | ["list"] => list(dbg, 5),
+        ["l", n] | ["list", n] => list(dbg, number(n)?),
+        ["set", reg, value] => {
+            let value = number(value)? as Word;
+            dbg.machine_mut().set_register(register(reg)?, value)?;
+            show_registers(dbg.machine());
+        }
+        ["overflow", name] => {
+            dbg.machine_mut().set_overflow(day16::parse_overflow(name)?);
+            show_registers(dbg.machine());
+        }
+        ["info"] => {
+            println!("Overflow policy: {}", dbg.machine().overflow());
+            for bp in dbg.breakpoints() {
+                println!("Breakpoint at {}", bp);
+            }
+            for reg in dbg.watches() {
+                println!("Watching r{}", reg);
+            }
+        }
+        ["h"] | ["help"] => println!("{}", HELP),
+        ["q"] | ["quit"] => return Ok(false),
+        _ => println!("Unknown command, type \"help\" for a list"),
+    }
+    Ok(true)
+}
+
+fn run_steps(dbg: &mut Debugger, count: usize) -> Result<()> {
+    for i in 0 .. count {
+        match dbg.step()? {
+            Stop::Step if i + 1 < count => (),
+            stop => {
+                show_stop(dbg, stop);
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn back(dbg: &mut Debugger, count: usize) {
+    for _ in 0 .. count {
+        if !dbg.back() {
+            println!("No more history");
+            break;
+        }
+    }
+    show_location(dbg);
+}
+
+fn show_stop(dbg: &Debugger, stop: Stop) {
+    match stop {
+        Stop::Step => (),
+        Stop::Breakpoint(bp) => println!("Breakpoint at {}", bp),
+        Stop::Watch { register, old, new } => println!("r{} changed: {} -> {}", register, old, new),
+        Stop::Halted => {
+            println!("Program halted after {} steps", dbg.machine().steps());
+            show_registers(dbg.machine());
+            return;
+        }
+    }
+    show_location(dbg);
+}
+
+/// Show the next instruction to run.
+fn show_location(dbg: &Debugger) {
+    let ip = dbg.machine().ip();
+    match dbg.program().code.get(ip) {
+        Some(instr) => println!("{:4}: {:<20}; {}", ip, instr.to_string(), instr.describe()),
+        None => println!("{:4}: (halted)", ip),
+    }
+}
+
+fn show_registers(machine: &Machine) {
+    let regs: Vec<String> = machine.registers().iter().enumerate()
+        .map(|(i, value)| format!("r{}={}", i, value))
+        .collect();
+    println!("ip={} {}", machine.ip(), regs.join(" "));
+}
+
+fn list(dbg: &Debugger, count: usize) {
+    let ip = dbg.machine().ip();
+    let start = ip.saturating_sub(count / 2);
+    for (addr, instr) in dbg.program().code.iter().enumerate().skip(start).take(count) {
+        let mark = if addr == ip { "=>" } else { "  " };
+        println!("{} {:4}: {:<20}; {}", mark, addr, instr.to_string(), instr.describe());
+    }
+}
+
+fn number(text: &str) -> Result<usize> {
+    text.parse().map_err(|_| Error::usage(format!("Invalid number: {:?}", text)))
+}
+
+fn register(text: &str) -> Result<Word> {
+    match text.strip_prefix('r') {
+        Some(num) => Ok(number(num)? as Word),
+        None => Err(Error::usage(format!("Expecting a register, such as \"r0\": {:?}", text))),
+    }
+}
+
+fn breakpoint(text: &str) -> Result<Breakpoint> {
+    match Opcode::from_name(text) {
+        Some(op) => Ok(Breakpoint::Opcode(op)),
+        None => Ok(Breakpoint::Ip(number(text)?)),
+    }
+}
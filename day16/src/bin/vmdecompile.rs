@@ -0,0 +1,42 @@
+//! Decompile a device program into pseudocode.
+//!
+//! Usage: `vmdecompile [--asm] [--dot] <input>`.  The input is a day 16
+//! style input, or with `--asm`, a program written in assembly.  With
+//! `--dot`, the control-flow graph is written in Graphviz's dot language
+//! instead, for `dot -Tsvg` and friends.
+
+use std::env;
+use common::{Error, Result};
+
+const USAGE: &str = "Usage: vmdecompile [--asm] [--dot] <input>";
+
+fn main() {
+    common::run(try_main);
+}
+
+fn try_main() -> Result<()> {
+    let mut asm = false;
+    let mut dot = false;
+    let mut input = None;
+
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--asm" => asm = true,
+            "--dot" => dot = true,
+            _ if input.is_none() && !arg.starts_with("--") => input = Some(arg),
+            _ => return Err(Error::usage(USAGE)),
+        }
+    }
+    let input = input.ok_or_else(|| Error::usage(USAGE))?;
+    let (program, _) = day16::load_tool_program(&input, asm)?;
+
+    let decompiled = vm::decompile(&program);
+    if dot {
+        print!("{}", decompiled.to_dot());
+    } else {
+        for line in decompiled.pseudocode() {
+            println!("{}", line);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Generate a day 16 style input, with a random numbering of the opcodes.
+//!
+//! Usage: `vmgen [--seed N] [--samples N] [--length N] [--extended]
+//! [output]`.  Writes to standard output if no output file is given.  The
+//! numbering used is printed to standard error, so the solver's answer can
+//! be checked.  With `--extended`, the input also uses the opcodes the
+//! device doesn't have, so the day 16 solver can't read it, but the
+//! mapping can still be recovered with the extended instruction set.
+
+use rand::{rngs::StdRng, SeedableRng};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufWriter, Write},
+};
+use common::{Error, Result};
+use vm::InstructionSet;
+
+const USAGE: &str = "Usage: vmgen [--seed N] [--samples N] [--length N] [--extended] [output]";
+
+fn main() {
+    common::run(try_main);
+}
+
+fn try_main() -> Result<()> {
+    let mut seed = None;
+    let mut samples = 800;
+    let mut length = 1000;
+    let mut set = InstructionSet::device();
+    let mut output = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => seed = Some(number(args.next())?),
+            "--samples" => samples = number(args.next())? as usize,
+            "--length" => length = number(args.next())? as usize,
+            "--extended" => set = InstructionSet::extended(),
+            _ if output.is_none() && !arg.starts_with("--") => output = Some(arg),
+            _ => return Err(Error::usage(USAGE)),
+        }
+    }
+
+    let mut rng = match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let generated = day16::generate(&mut rng, &set, samples, length)?;
+
+    match &output {
+        Some(path) => {
+            let file = File::create(path).map_err(|e| Error::io_path(path.as_ref(), e))?;
+            let mut out = BufWriter::new(file);
+            generated.write(&mut out)
+                .and_then(|_| out.flush())
+                .map_err(|e| Error::io_write(path.as_ref(), e))?;
+        }
+        None => {
+            generated.write(&mut io::stdout().lock())
+                .map_err(|e| Error::io("Unable to write to standard output", e))?;
+        }
+    }
+
+    let names: Vec<_> = generated.opmap.iter().enumerate()
+        .map(|(code, op)| format!("{}={}", code, op.name()))
+        .collect();
+    eprintln!("{}", names.join(" "));
+    Ok(())
+}
+
+fn number(arg: Option<String>) -> Result<u64> {
+    let arg = arg.ok_or_else(|| Error::usage(USAGE))?;
+    arg.parse().map_err(|_| Error::usage(format!("Invalid number: {:?}", arg)))
+}
@@ -0,0 +1,93 @@
+//! Run a device program, recording a trace of every instruction, and
+//! optionally a profile of where it spent its time.
+//!
+//! Usage: `vmtrace [--asm] [--trace <file>] [--profile] [--overflow
+//! <policy>] <input>`.  The input is a day 16 style input, or with `--asm`,
+//! a program written in assembly.  A trace file ending in ".csv" is written
+//! as CSV, and anything else as JSON lines.  The overflow policy is one of
+//! wrapping (the default), checked, saturating, or widened.
+
+use std::{
+    env,
+    fs::File,
+    io::BufWriter,
+};
+use common::{Error, Result};
+use vm::{Profile, TraceFormat, TraceWriter};
+
+const USAGE: &str = "Usage: vmtrace [--asm] [--trace <file>] [--profile] [--overflow <policy>] <input>";
+
+fn main() {
+    common::run(try_main);
+}
+
+fn try_main() -> Result<()> {
+    let mut asm = false;
+    let mut trace = None;
+    let mut profile = None;
+    let mut overflow = None;
+    let mut input = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--asm" => asm = true,
+            "--trace" => trace = Some(args.next().ok_or_else(|| Error::usage(USAGE))?),
+            "--profile" => profile = Some(Profile::new()),
+            "--overflow" => {
+                let name = args.next().ok_or_else(|| Error::usage(USAGE))?;
+                overflow = Some(day16::parse_overflow(&name)?);
+            }
+            _ if input.is_none() && !arg.starts_with("--") => input = Some(arg),
+            _ => return Err(Error::usage(USAGE)),
+        }
+    }
+    let input = input.ok_or_else(|| Error::usage(USAGE))?;
+    let (program, mut machine) = day16::load_tool_program(&input, asm)?;
+    if let Some(overflow) = overflow {
+        machine.set_overflow(overflow);
+    }
+
+    let mut writer = match &trace {
+        Some(path) => {
+            let format = if path.ends_with(".csv") { TraceFormat::Csv } else { TraceFormat::Jsonl };
+            let file = File::create(path).map_err(|e| Error::io_path(path.as_ref(), e))?;
+            Some(TraceWriter::new(BufWriter::new(file), format))
+        }
+        None => None,
+    };
+
+    // If the program fails, by hitting the step limit, or overflowing,
+    // the trace and the profile up to that point are still wanted, to see
+    // what went wrong.
+    let failed = loop {
+        let event = match machine.step_traced(&program) {
+            Ok(Some(event)) => event,
+            Ok(None) => break None,
+            Err(err) => break Some(err),
+        };
+        if let (Some(writer), Some(path)) = (&mut writer, &trace) {
+            writer.write(&event).map_err(|e| Error::io_write(path.as_ref(), e))?;
+        }
+        if let Some(profile) = &mut profile {
+            profile.record(&event);
+        }
+    };
+    if let (Some(writer), Some(path)) = (writer, &trace) {
+        writer.finish().map_err(|e| Error::io_write(path.as_ref(), e))?;
+    }
+
+    let state = if failed.is_some() { "Stopped" } else { "Halted" };
+    println!("{} after {} steps, registers: {:?} ({} arithmetic)",
+             state, machine.steps(), machine.registers(), machine.overflow());
+    if let Some(profile) = profile {
+        println!();
+        for line in profile.report(&program) {
+            println!("{}", line);
+        }
+    }
+    match failed {
+        Some(err) => Err(err.into()),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,115 @@
+//! Generating puzzle inputs, for testing the solver against more than the
+//! one real input.
+//!
+//! A generated input uses a random numbering of the opcodes in an
+//! instruction set, which the solver has to work out again from the
+//! samples.
+
+use rand::{seq::SliceRandom, Rng};
+use std::io::{self, Write};
+use common::{Error, Result};
+use vm::{Instruction, InstructionSet, Machine, Opcode, Program, Sample, Word};
+use crate::{REGISTERS, WIDTH};
+
+/// Register values in samples are kept small, like in the real input, so
+/// the comparison opcodes have a chance of being true.
+const MAX_VALUE: Word = 16;
+
+/// How many random instructions to try, for each sample or each step of
+/// the program, before deciding none of them can work.
+const MAX_TRIES: usize = 1000;
+
+/// A generated input, along with the secret numbering used to build it.
+#[derive(Clone, Debug)]
+pub struct Generated {
+    /// The opcode for each number.
+    pub opmap: Vec<Opcode>,
+    pub samples: Vec<Sample>,
+    pub program: Program,
+}
+
+/// Generate an input using the opcodes in the set, with the given number
+/// of samples, and a program of the given length.  Each sample uses a
+/// randomly chosen opcode number, so with only a few samples, some numbers
+/// may never be seen.  It is an error if the set is empty, or if its
+/// opcodes keep dividing by zero.
+pub fn generate<R: Rng>(rng: &mut R, set: &InstructionSet, samples: usize, length: usize) -> Result<Generated> {
+    if set.is_empty() {
+        return Err(Error::usage("Can't generate an input with no opcodes"));
+    }
+    let mut opmap: Vec<Opcode> = set.iter().collect();
+    opmap.shuffle(rng);
+
+    let samples = (0 .. samples).map(|_| {
+        let code = rng.gen_range(0 .. opmap.len());
+        // Division by zero isn't something a sample can show, so try again.
+        for _ in 0 .. MAX_TRIES {
+            let instr = random_instruction(rng, opmap[code]);
+            let before: Vec<Word> = (0 .. REGISTERS).map(|_| rng.gen_range(0 .. MAX_VALUE)).collect();
+
+            let mut machine = Machine::with_registers(&before, WIDTH);
+            if machine.exec(&instr).is_ok() {
+                return Ok(Sample {
+                    before,
+                    instr: [code as Word, instr.a, instr.b, instr.c],
+                    after: machine.registers().to_vec(),
+                    line: None,
+                });
+            }
+        }
+        Err(Error::solve(format!("Couldn't generate a sample for {} in {} tries",
+                                 opmap[code].name(), MAX_TRIES)))
+    }).collect::<Result<_>>()?;
+
+    // The program doesn't bind the instruction pointer, so it always runs
+    // straight through.  It's run as it is built, to leave out
+    // instructions that would divide by zero.
+    let mut machine = Machine::new(REGISTERS, WIDTH);
+    let code = (0 .. length).map(|step| {
+        for _ in 0 .. MAX_TRIES {
+            // The set isn't empty, so there's always one to choose.
+            let op = *opmap.choose(rng).unwrap();
+            let instr = random_instruction(rng, op);
+            if machine.exec(&instr).is_ok() {
+                return Ok(instr);
+            }
+        }
+        Err(Error::solve(format!("Couldn't generate instruction {} of the program in {} tries, \
+                                  registers are {:?}", step, MAX_TRIES, machine.registers())))
+    }).collect::<Result<_>>()?;
+
+    Ok(Generated {
+        opmap,
+        samples,
+        program: Program::new(None, code),
+    })
+}
+
+/// A random instruction with the given opcode, that only uses the device's
+/// registers.
+fn random_instruction<R: Rng>(rng: &mut R, op: Opcode) -> Instruction {
+    let regs = REGISTERS as Word;
+    let a = if op.a_is_register() { rng.gen_range(0 .. regs) } else { rng.gen_range(0 .. MAX_VALUE) };
+    let b = if op.b_is_register() { rng.gen_range(0 .. regs) } else { rng.gen_range(0 .. MAX_VALUE) };
+    Instruction::new(op, a, b, rng.gen_range(0 .. regs))
+}
+
+impl Generated {
+    /// Write the input, in the same format as the real one.
+    pub fn write<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        for sample in &self.samples {
+            writeln!(out, "Before: {:?}", sample.before)?;
+            writeln!(out, "{} {} {} {}", sample.instr[0], sample.instr[1], sample.instr[2], sample.instr[3])?;
+            writeln!(out, "After:  {:?}", sample.after)?;
+            writeln!(out)?;
+        }
+        writeln!(out)?;
+        writeln!(out)?;
+
+        let raw = vm::encode(&self.program, &self.opmap).expect("every opcode is numbered");
+        for [op, a, b, c] in raw {
+            writeln!(out, "{} {} {} {}", op, a, b, c)?;
+        }
+        Ok(())
+    }
+}
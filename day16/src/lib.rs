@@ -0,0 +1,269 @@
+use regex::{Captures, Regex};
+use std::{
+    result,
+    slice,
+};
+use common::{eof_error, Answer, Error, InputSource, Line, ParseError, Result, Solver};
+use vm::{Compiled, InstructionSet, Machine, Opcode, Overflow, Program, Sample, Word};
+
+mod generate;
+
+pub use generate::{generate, Generated};
+
+type Register = Word;
+
+/// The device has four registers, each holding 32 bits.
+const REGISTERS: usize = 4;
+const WIDTH: u32 = 32;
+
+/// Programs with an instruction pointer binding can loop, so give up on
+/// any that run this long.
+const STEP_LIMIT: u64 = 1_000_000_000;
+
+pub struct Day16;
+
+impl Solver for Day16 {
+    fn part1(&self, input: &InputSource) -> Result<Answer> {
+        let input = Input::from_input(input)?;
+        Ok(input.solve1().into())
+    }
+
+    fn part2(&self, input: &InputSource) -> Result<Answer> {
+        let input = Input::from_input(input)?;
+
+        Ok(input.solve2()?.into())
+    }
+}
+
+/// Read the day's input, work out the opcode numbers from the samples, and
+/// decode the program that follows them.
+pub fn load_program(input: &InputSource) -> Result<Program> {
+    Input::from_input(input)?.decode()
+}
+
+/// Read just the samples from the day's input.
+pub fn load_samples(input: &InputSource) -> Result<Vec<Sample>> {
+    Ok(Input::from_input(input)?.samples)
+}
+
+/// Load a program for one of the tools.  This is either a day 16 style
+/// input, or, if `asm` is set, a program written in assembly.  Returns the
+/// program, and a machine to run it on.  Assembly programs get 64-bit
+/// registers, and as many as they use, but at least as many as the device.
+pub fn load_tool_program(path: &str, asm: bool) -> Result<(Program, Machine)> {
+    let input = InputSource::File(path.into());
+    if asm {
+        let program = vm::assemble(&input.lines("asm", path)?)?;
+        let mut machine = Machine::new(program.register_count().max(REGISTERS), 64);
+        machine.set_step_limit(Some(STEP_LIMIT));
+        Ok((program, machine))
+    } else {
+        Ok((load_program(&input)?, device()))
+    }
+}
+
+/// Parse the name of an overflow policy, given to one of the tools.
+pub fn parse_overflow(name: &str) -> Result<Overflow> {
+    Overflow::from_name(name).ok_or_else(|| {
+        Error::usage(format!("Unknown overflow policy {:?}, expecting wrapping, checked, saturating, or widened", name))
+    })
+}
+
+/// A machine the same shape as the device: four registers of 32 bits.  It
+/// gives up on programs that run too long.
+pub fn device() -> Machine {
+    let mut machine = Machine::new(REGISTERS, WIDTH);
+    machine.set_step_limit(Some(STEP_LIMIT));
+    machine
+}
+
+#[derive(Debug)]
+struct Input {
+    samples: Vec<Sample>,
+
+    /// The register bound to the instruction pointer, given by a "#ip N"
+    /// line before the program.
+    ip_register: Option<Register>,
+    program: Vec<[Register; 4]>,
+}
+
+impl Input {
+    fn from_input(input: &InputSource) -> Result<Input> {
+        // Be lenient about spacing, but nothing else.
+        let before_re = Regex::new(r"^\s*Before:\s*\[\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*\]\s*$")?;
+        let after_re = Regex::new(r"^\s*After:\s*\[\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*,\s*(\d+)\s*\]\s*$")?;
+        let op_re = Regex::new(r"^\s*(\d+)\s+(\d+)\s+(\d+)\s+(\d+)\s*$")?;
+        let ip_re = Regex::new(r"^\s*#ip\s+(\d+)\s*$")?;
+
+        let all_lines = input.lines("day16", "input.txt")?;
+        let mut lines = Scanner {
+            lines: all_lines.iter(),
+            file: input.display_name("day16", "input.txt"),
+            count: all_lines.len(),
+        };
+
+        // The samples come first.  They end at the first line that doesn't
+        // start one.
+        let mut samples = vec![];
+        loop {
+            lines.skip_blank();
+            let line = match lines.peek() {
+                Some(line) if is_tagged(line, "Before:") => line,
+                _ => break,
+            };
+            let before = lines.expect_line(&before_re, "\"Before: [a, b, c, d]\"")?;
+            check_width(line, &before)?;
+            let instr = lines.expect_line(&op_re, "the sample's instruction")?;
+            let after_line = lines.peek();
+            let after = lines.expect_line(&after_re, "\"After: [a, b, c, d]\"")?;
+            if let Some(after_line) = after_line {
+                check_width(after_line, &after)?;
+            }
+
+            samples.push(Sample {
+                before: before.to_vec(),
+                instr,
+                after: after.to_vec(),
+                line: Some(line.num),
+            });
+        }
+
+        // Then the program, with an optional "#ip" line before it.  All that
+        // is left should be instructions.
+        let mut ip_register = None;
+        let mut program = vec![];
+        loop {
+            lines.skip_blank();
+            let line = match lines.peek() {
+                Some(line) => line,
+                None => break,
+            };
+            if ip_re.is_match(&line.text) {
+                if !program.is_empty() || ip_register.is_some() {
+                    return Err(line.error_line("\"#ip\" must come once, before the program").into());
+                }
+                ip_register = Some(lines.expect_value(&ip_re, "\"#ip N\"")?);
+            } else if is_tagged(line, "Before:") || is_tagged(line, "After:") {
+                return Err(line.error_line("sample found after the program has started").into());
+            } else {
+                program.push(lines.expect_line(&op_re, "an instruction")?);
+            }
+        }
+
+        Ok(Input{
+            samples,
+            ip_register,
+            program,
+        })
+    }
+
+    /// Count how many of the samples behave like three or more of the
+    /// device's opcodes.
+    fn solve1(&self) -> usize {
+        let set = InstructionSet::device();
+        let mut total_count = 0;
+        for sample in &self.samples {
+            let mut count = 0;
+            for op in set.iter() {
+                if sample.matches(op, WIDTH) {
+                    count += 1;
+                }
+            }
+            if count >= 3 {
+                total_count += 1;
+            }
+        }
+        total_count
+    }
+
+    /// Run the sample program, using the opcode mapping from the samples,
+    /// returning the final value of register 0.
+    fn solve2(&self) -> Result<Register> {
+        let program = self.decode()?;
+        let mut machine = device();
+        Compiled::new(&program, REGISTERS)?.run(&mut machine)?;
+        Ok(machine.register(0)?)
+    }
+
+    /// Decode the sample program, using the opcode mapping from the
+    /// samples.
+    fn decode(&self) -> Result<Program> {
+        Ok(Program::decode(self.ip_register, &self.program, &self.opcode_map()?)?)
+    }
+
+    /// Given a series of statistical samples, determine what the mapping must
+    /// be between the integers and the opcodes.  It is an error if the
+    /// samples contradict each other, or don't pin down every opcode.
+    fn opcode_map(&self) -> Result<Vec<Opcode>> {
+        Ok(vm::find_mapping(&self.samples, &InstructionSet::device(), WIDTH)?)
+    }
+}
+
+/// The lines of the input, as they are being scanned through.
+struct Scanner<'a> {
+    lines: slice::Iter<'a, Line>,
+
+    /// The name of the input, and its total number of lines, for reporting
+    /// unexpected end of file.
+    file: String,
+    count: usize,
+}
+
+impl<'a> Scanner<'a> {
+    /// The next line, without reading it.
+    fn peek(&self) -> Option<&'a Line> {
+        self.lines.clone().next()
+    }
+
+    /// Skip over any blank lines.
+    fn skip_blank(&mut self) {
+        while self.peek().is_some_and(|line| line.text.trim().is_empty()) {
+            self.lines.next();
+        }
+    }
+
+    /// Read a line that must match the given regex, and return the four
+    /// values it captures.  `what` describes the expected line, for the
+    /// error message.
+    fn expect_line(&mut self, re: &Regex, what: &str) -> result::Result<[Register; 4], ParseError> {
+        let (line, cap) = self.expect_match(re, what)?;
+        Ok([
+            line.parse_cap(&cap, 1)?,
+            line.parse_cap(&cap, 2)?,
+            line.parse_cap(&cap, 3)?,
+            line.parse_cap(&cap, 4)?,
+        ])
+    }
+
+    /// Read a line that must match the given single-valued regex.
+    fn expect_value(&mut self, re: &Regex, what: &str) -> result::Result<Register, ParseError> {
+        let (line, cap) = self.expect_match(re, what)?;
+        line.parse_cap(&cap, 1)
+    }
+
+    fn expect_match(&mut self, re: &Regex, what: &str)
+        -> result::Result<(&'a Line, Captures<'a>), ParseError>
+    {
+        match self.lines.next() {
+            None => Err(eof_error(&self.file, self.count, format!("expecting {}", what))),
+            Some(line) => match re.captures(&line.text) {
+                Some(cap) => Ok((line, cap)),
+                None => Err(line.error_line(format!("expecting {}", what))),
+            },
+        }
+    }
+}
+
+/// Does this line start with the given tag?
+fn is_tagged(line: &Line, tag: &str) -> bool {
+    line.text.trim_start().starts_with(tag)
+}
+
+/// Make sure the register values from a sample fit in the device's
+/// registers.
+fn check_width(line: &Line, values: &[Register; 4]) -> result::Result<(), ParseError> {
+    match values.iter().find(|&&v| v >> WIDTH != 0) {
+        Some(v) => Err(line.error_line(format!("register value {} doesn't fit in {} bits", v, WIDTH))),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,105 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use common::{Error, InputSource};
+use vm::{find_mapping, find_mappings, InstructionSet, MappingError, Opcode};
+
+const WIDTH: u32 = 32;
+
+/// Generate inputs with varying numbers of samples, and check that the
+/// solver either recovers the secret numbering, or says it can't.
+#[test]
+fn recovers_numbering() {
+    let set = InstructionSet::device();
+    let mut unique = 0;
+    let mut ambiguous = 0;
+
+    for seed in 0 .. 60 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let count = rng.gen_range(1 .. 300);
+        let generated = day16::generate(&mut rng, &set, count, 20).unwrap();
+
+        let mut text = vec![];
+        generated.write(&mut text).unwrap();
+        let input = InputSource::Text(String::from_utf8(text).unwrap());
+
+        let samples = day16::load_samples(&input).unwrap();
+        assert_eq!(samples.len(), count);
+        for (read, written) in samples.iter().zip(&generated.samples) {
+            assert_eq!((&read.before, read.instr, &read.after),
+                       (&written.before, written.instr, &written.after));
+        }
+
+        let mapping = find_mappings(&samples, &set, WIDTH, 1000).unwrap();
+        for solution in &mapping.solutions {
+            for sample in &samples {
+                assert!(sample.matches(solution[sample.code() as usize], WIDTH));
+            }
+        }
+
+        match find_mapping(&samples, &set, WIDTH) {
+            Ok(opmap) => {
+                unique += 1;
+                assert_eq!(opmap, generated.opmap);
+                assert_eq!(day16::load_program(&input).unwrap(), generated.program);
+            }
+            Err(MappingError::Ambiguous(codes)) => {
+                ambiguous += 1;
+                assert!(!codes.is_empty());
+                assert_eq!(codes, mapping.ambiguous);
+                if mapping.complete {
+                    assert!(mapping.solutions.contains(&generated.opmap));
+                }
+                for (code, ops) in &codes {
+                    assert!(ops.contains(&generated.opmap[*code as usize]));
+                }
+            }
+            Err(err) => panic!("seed {}: {}", seed, err),
+        }
+    }
+
+    // Make sure both cases were tried.
+    assert!(unique > 0);
+    assert!(ambiguous > 0);
+}
+
+/// Inputs using the extensions too can't be solved as day 16 inputs, but
+/// the numbering can still be recovered with the extended set.
+#[test]
+fn recovers_extended_numbering() {
+    let set = InstructionSet::extended();
+    for seed in 0 .. 10 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let generated = day16::generate(&mut rng, &set, 1500, 50).unwrap();
+        assert_eq!(find_mapping(&generated.samples, &set, WIDTH).unwrap(), generated.opmap, "seed {}", seed);
+
+        let mut text = vec![];
+        generated.write(&mut text).unwrap();
+        let input = InputSource::Text(String::from_utf8(text).unwrap());
+        assert!(day16::load_samples(&input).is_ok());
+    }
+}
+
+/// There's nothing to generate from an empty set.
+#[test]
+fn empty_set() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let set = InstructionSet::new(vec![]).unwrap();
+    match day16::generate(&mut rng, &set, 10, 10) {
+        Err(Error::Usage(_)) => (),
+        other => panic!("expected a usage error, got {:?}", other.map(|g| g.opmap)),
+    }
+}
+
+/// With only `divr`, samples can pick registers that aren't zero, but the
+/// program starts with every register zero, so it can never take a step.
+#[test]
+fn only_dividing_by_zero() {
+    let mut rng = StdRng::seed_from_u64(0);
+    let set = InstructionSet::new(vec![Opcode::Divr]).unwrap();
+    let generated = day16::generate(&mut rng, &set, 10, 0).unwrap();
+    assert_eq!(generated.samples.len(), 10);
+
+    match day16::generate(&mut rng, &set, 10, 1) {
+        Err(Error::Solve(message)) => assert!(message.contains("instruction 0"), "{}", message),
+        other => panic!("expected a solve error, got {:?}", other.map(|g| g.opmap)),
+    }
+}
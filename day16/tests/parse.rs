@@ -0,0 +1,70 @@
+use common::{Error, InputSource};
+
+fn text(lines: &[&str]) -> InputSource {
+    InputSource::Text(lines.join("\n") + "\n")
+}
+
+#[test]
+fn samples() {
+    let input = text(&[
+        "  Before: [ 3,2,  1, 1 ]  ",
+        "9 2\t1  2",
+        "After:[3, 2, 2, 1]",
+        "",
+        "",
+        "Before: [123, 4567, 0, 4294967295]",
+        "12 0 1 3",
+        "After:  [123, 4567, 0, 4691]",
+        "",
+        "#ip 2",
+        "0 1 2 3",
+    ]);
+    let samples = day16::load_samples(&input).unwrap();
+    assert_eq!(samples.len(), 2);
+
+    assert_eq!(samples[0].before, vec![3, 2, 1, 1]);
+    assert_eq!(samples[0].instr, [9, 2, 1, 2]);
+    assert_eq!(samples[0].after, vec![3, 2, 2, 1]);
+    assert_eq!(samples[0].line, Some(1));
+
+    assert_eq!(samples[1].before, vec![123, 4567, 0, 4294967295]);
+    assert_eq!(samples[1].instr, [12, 0, 1, 3]);
+    assert_eq!(samples[1].after, vec![123, 4567, 0, 4691]);
+    assert_eq!(samples[1].line, Some(6));
+}
+
+#[test]
+fn errors() {
+    let sample = ["Before: [3, 2, 1, 1]", "9 2 1 2", "After:  [3, 2, 2, 1]"];
+    let cases: &[(&[&str], (usize, usize), &str)] = &[
+        (&["Before: [3, 2, 1]", "9 2 1 2", "After:  [3, 2, 2, 1]"],
+         (1, 1), "expecting \"Before: [a, b, c, d]\""),
+        (&["Before: [3, 2, 1, 1]", "9 2 1", "After:  [3, 2, 2, 1]"],
+         (2, 1), "expecting the sample's instruction"),
+        (&["Before: [3, 2, 1, 1]", "9 2 1 2", "After  [3, 2, 2, 1]"],
+         (3, 1), "expecting \"After: [a, b, c, d]\""),
+        (&["Before: [3, 2, 1, 1]", "9 2 1 2"],
+         (3, 1), "expecting \"After: [a, b, c, d]\""),
+        (&["Before: [3, 99999999999999999999, 1, 1]", "9 2 1 2", "After:  [3, 2, 2, 1]"],
+         (1, 13), "invalid value \"99999999999999999999\": number too large to fit in target type"),
+        (&["Before: [3, 2, 1, 4294967296]", "9 2 1 2", "After:  [3, 2, 2, 1]"],
+         (1, 1), "register value 4294967296 doesn't fit in 32 bits"),
+        (&[sample[0], sample[1], sample[2], "0 1 2 3", "#ip 2"],
+         (5, 1), "\"#ip\" must come once, before the program"),
+        (&["#ip 2", "#ip 3", "0 1 2 3"],
+         (2, 1), "\"#ip\" must come once, before the program"),
+        (&["#ip 2", "0 1 2 3", sample[0]],
+         (3, 1), "sample found after the program has started"),
+        (&["0 1 2 3", "0 1 two 3"],
+         (2, 1), "expecting an instruction"),
+    ];
+    for &(lines, (line, column), message) in cases {
+        match day16::load_samples(&text(lines)) {
+            Err(Error::Parse(err)) => {
+                assert_eq!(err.message, message, "{:?}", lines);
+                assert_eq!((err.line, err.column), (line, column), "{:?}", lines);
+            }
+            other => panic!("{:?}: expected a parse error, got {:?}", lines, other.map_err(|e| e.to_string())),
+        }
+    }
+}
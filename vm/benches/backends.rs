@@ -0,0 +1,58 @@
+//! Compare the speed of the interpreter and the compiled backend, on a
+//! program that spends all of its time in a loop.  Run with `cargo bench
+//! -p vm`.
+
+use std::time::{Duration, Instant};
+use common::{InputSource, Result};
+use vm::{assemble, Compiled, Machine};
+
+/// The loop from the test corpus, which adds up 1 to r4, r4 times over.
+const PROGRAM: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus/loop.asm");
+
+const COUNT: u64 = 2000;
+const ROUNDS: usize = 5;
+
+fn main() {
+    common::run(try_main);
+}
+
+fn try_main() -> Result<()> {
+    let program = assemble(&InputSource::File(PROGRAM.into()).lines("bench", "")?)?;
+    let machine = Machine::with_registers(&[0, 0, 0, 0, COUNT, 0], 64);
+
+    let (interpreted, slow) = best_of(|| {
+        let mut m = machine.clone();
+        m.run(&program)?;
+        Ok(m)
+    })?;
+    let (compiled, fast) = best_of(|| {
+        let mut m = machine.clone();
+        Compiled::new(&program, m.registers().len())?.run(&mut m)?;
+        Ok(m)
+    })?;
+    assert_eq!(interpreted, compiled);
+
+    let steps = interpreted.steps();
+    report("interpreted", steps, slow);
+    report("compiled", steps, fast);
+    println!("speedup: {:.1}x", slow.as_secs_f64() / fast.as_secs_f64());
+    Ok(())
+}
+
+/// Run `f` a few times, returning its result, and the fastest time.
+fn best_of<F: FnMut() -> Result<Machine>>(mut f: F) -> Result<(Machine, Duration)> {
+    let mut best = None;
+    let mut result = None;
+    for _ in 0 .. ROUNDS {
+        let start = Instant::now();
+        result = Some(f()?);
+        let elapsed = start.elapsed();
+        best = Some(best.map_or(elapsed, |b: Duration| b.min(elapsed)));
+    }
+    Ok((result.unwrap(), best.unwrap()))
+}
+
+fn report(name: &str, steps: u64, time: Duration) {
+    println!("{:12} {} steps in {:?} ({:.1} Msteps/s)", name, steps, time,
+             steps as f64 / time.as_secs_f64() / 1e6);
+}
@@ -0,0 +1,165 @@
+//! Reading and writing programs as assembly.
+//!
+//! Each instruction is written as its mnemonic and three arguments.
+//! Register arguments are written as `r` and the register number, and
+//! immediate values as just the number, so `addi r1 5 r0` adds 5 to
+//! register 1, and puts the result in register 0.  The set opcodes ignore
+//! their `b` argument, which is written as a number.  A program can start
+//! with a `#ip N` directive to bind the instruction pointer, and anything
+//! after a `;` is a comment.
+
+use std::{
+    fmt,
+    result,
+};
+use common::{Line, ParseError};
+use crate::{
+    Error,
+    Instruction,
+    Opcode,
+    Operation,
+    Program,
+    Result,
+    Word,
+};
+
+/// Assemble the lines of a program.
+pub fn assemble(lines: &[Line]) -> result::Result<Program, ParseError> {
+    let mut program = Program::default();
+
+    for line in lines {
+        let text = match line.text.find(';') {
+            Some(pos) => &line.text[..pos],
+            None => &line.text[..],
+        };
+        let tokens = tokens(text);
+        let (offset, first) = match tokens.first() {
+            Some(&token) => token,
+            None => continue,
+        };
+
+        if first == "#ip" {
+            if !program.code.is_empty() || program.ip_register.is_some() {
+                return Err(line.error(offset, "#ip must come once, before the instructions"));
+            }
+            if tokens.len() != 2 {
+                return Err(line.error(offset, "expecting \"#ip N\""));
+            }
+            let (offset, field) = tokens[1];
+            program.ip_register = Some(line.parse_at(offset, field)?);
+            continue;
+        }
+
+        let op = Opcode::from_name(first)
+            .ok_or_else(|| line.error(offset, format!("unknown opcode {:?}", first)))?;
+        if tokens.len() != 4 {
+            return Err(line.error(offset, format!("{} takes three arguments", first)));
+        }
+        let a = operand(line, tokens[1], op.a_is_register())?;
+        let b = operand(line, tokens[2], op.b_is_register())?;
+        let c = operand(line, tokens[3], true)?;
+        program.code.push(Instruction::new(op, a, b, c));
+    }
+
+    Ok(program)
+}
+
+/// Split a line into whitespace separated tokens, with the offset of each.
+fn tokens(text: &str) -> Vec<(usize, &str)> {
+    let mut result = vec![];
+    let mut start = None;
+    for (i, ch) in text.char_indices() {
+        if ch.is_whitespace() {
+            if let Some(s) = start.take() {
+                result.push((s, &text[s .. i]));
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+    if let Some(s) = start {
+        result.push((s, &text[s ..]));
+    }
+    result
+}
+
+/// Decode a single argument, which must be a register if `is_register`,
+/// and a plain number otherwise.
+fn operand(line: &Line, (offset, field): (usize, &str), is_register: bool)
+    -> result::Result<Word, ParseError>
+{
+    match (is_register, field.strip_prefix('r')) {
+        (true, Some(reg)) => line.parse_at(offset + 1, reg),
+        (true, None) => Err(line.error(offset, "expecting a register, such as \"r0\"")),
+        (false, Some(_)) => Err(line.error(offset, "expecting a value, not a register")),
+        (false, None) => line.parse_at(offset, field),
+    }
+}
+
+/// Convert a program to numeric form, using the number each opcode has in
+/// the opcode map.
+pub fn encode(program: &Program, opmap: &[Opcode]) -> Result<Vec<[Word; 4]>> {
+    program.code.iter().map(|instr| {
+        let code = opmap.iter().position(|&op| op == instr.op)
+            .ok_or(Error::Unmapped(instr.op))?;
+        Ok([code as Word, instr.a, instr.b, instr.c])
+    }).collect()
+}
+
+/// Write out a program as assembly, with a comment on each instruction
+/// showing what it computes.
+pub fn disassemble(program: &Program) -> Vec<String> {
+    let mut lines = vec![];
+    if let Some(reg) = program.ip_register {
+        lines.push(format!("#ip {}", reg));
+    }
+    for instr in &program.code {
+        let text = instr.to_string();
+        lines.push(format!("{:<20}; {}", text, instr.describe()));
+    }
+    lines
+}
+
+impl Instruction {
+    /// Describe what this instruction does, such as "r0 = r1 + 5".
+    pub fn describe(&self) -> String {
+        let a = Arg(self.a, self.op.a_is_register());
+        let b = Arg(self.b, self.op.b_is_register());
+        let c = Arg(self.c, true);
+        match self.op.operation() {
+            Operation::Add => format!("{} = {} + {}", c, a, b),
+            Operation::Mul => format!("{} = {} * {}", c, a, b),
+            Operation::Ban => format!("{} = {} & {}", c, a, b),
+            Operation::Bor => format!("{} = {} | {}", c, a, b),
+            Operation::Set => format!("{} = {}", c, a),
+            Operation::Gt => format!("{} = {} > {}", c, a, b),
+            Operation::Eq => format!("{} = {} == {}", c, a, b),
+            Operation::Div => format!("{} = {} / {}", c, a, b),
+            Operation::Mod => format!("{} = {} % {}", c, a, b),
+            Operation::Shl => format!("{} = {} << {}", c, a, b),
+            Operation::Shr => format!("{} = {} >> {}", c, a, b),
+        }
+    }
+}
+
+/// An argument, shown as a register or as a value.
+struct Arg(Word, bool);
+
+impl fmt::Display for Arg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.1 {
+            write!(f, "r{}", self.0)
+        } else {
+            write!(f, "{}", self.0)
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} {}", self.op.name(),
+               Arg(self.a, self.op.a_is_register()),
+               Arg(self.b, self.op.b_is_register()),
+               Arg(self.c, true))
+    }
+}
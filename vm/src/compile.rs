@@ -0,0 +1,258 @@
+//! A faster way to run programs.
+//!
+//! The interpreter in `Machine::step` looks up each opcode's mode and
+//! operation, and checks each register number, every time it runs an
+//! instruction.  Compiling a program does all of that once, turning each
+//! instruction into a `Code` with its operands already resolved, so that
+//! running it is a single match.
+
+use crate::{
+    Error,
+    Machine,
+    Mode,
+    Opcode,
+    Operation,
+    Overflow,
+    Program,
+    Result,
+    Word,
+    machine::{shl, shr},
+};
+
+/// A program compiled for machines with a given number of registers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Compiled {
+    code: Vec<Code>,
+    ip_register: Option<usize>,
+    registers: usize,
+}
+
+/// A single compiled instruction.  Register operands are indices that have
+/// already been checked, and immediate operands are the values themselves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Code {
+    AddR(usize, usize, usize),
+    AddI(usize, Word, usize),
+    MulR(usize, usize, usize),
+    MulI(usize, Word, usize),
+    BanR(usize, usize, usize),
+    BanI(usize, Word, usize),
+    BorR(usize, usize, usize),
+    BorI(usize, Word, usize),
+    SetR(usize, usize),
+    SetI(Word, usize),
+    GtIR(Word, usize, usize),
+    GtRI(usize, Word, usize),
+    GtRR(usize, usize, usize),
+    EqIR(Word, usize, usize),
+    EqRI(usize, Word, usize),
+    EqRR(usize, usize, usize),
+    DivR(usize, usize, usize),
+    DivI(usize, Word, usize),
+    ModR(usize, usize, usize),
+    ModI(usize, Word, usize),
+    ShlR(usize, usize, usize),
+    ShlI(usize, Word, usize),
+    ShrR(usize, usize, usize),
+    ShrI(usize, Word, usize),
+}
+
+impl Compiled {
+    /// Compile a program, to run on machines with the given number of
+    /// registers.  Unlike the interpreter, which only complains about a
+    /// bad register number when the instruction using it runs, this
+    /// checks every instruction up front.
+    pub fn new(program: &Program, registers: usize) -> Result<Compiled> {
+        let reg = |r: Word| -> Result<usize> {
+            if r < registers as Word {
+                Ok(r as usize)
+            } else {
+                Err(Error::BadRegister { register: r, count: registers })
+            }
+        };
+
+        let code = program.code.iter().map(|instr| {
+            let (a, b, c) = (instr.a, instr.b, instr.c);
+            let code = match (instr.op.operation(), instr.op.mode()) {
+                (Operation::Add, Mode::RegReg) => Code::AddR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Add, _) => Code::AddI(reg(a)?, b, reg(c)?),
+                (Operation::Mul, Mode::RegReg) => Code::MulR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Mul, _) => Code::MulI(reg(a)?, b, reg(c)?),
+                (Operation::Ban, Mode::RegReg) => Code::BanR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Ban, _) => Code::BanI(reg(a)?, b, reg(c)?),
+                (Operation::Bor, Mode::RegReg) => Code::BorR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Bor, _) => Code::BorI(reg(a)?, b, reg(c)?),
+                (Operation::Set, Mode::ImmReg) => Code::SetI(a, reg(c)?),
+                (Operation::Set, _) => Code::SetR(reg(a)?, reg(c)?),
+                (Operation::Gt, Mode::ImmReg) => Code::GtIR(a, reg(b)?, reg(c)?),
+                (Operation::Gt, Mode::RegImm) => Code::GtRI(reg(a)?, b, reg(c)?),
+                (Operation::Gt, Mode::RegReg) => Code::GtRR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Eq, Mode::ImmReg) => Code::EqIR(a, reg(b)?, reg(c)?),
+                (Operation::Eq, Mode::RegImm) => Code::EqRI(reg(a)?, b, reg(c)?),
+                (Operation::Eq, Mode::RegReg) => Code::EqRR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Div, Mode::RegReg) => Code::DivR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Div, _) => Code::DivI(reg(a)?, b, reg(c)?),
+                (Operation::Mod, Mode::RegReg) => Code::ModR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Mod, _) => Code::ModI(reg(a)?, b, reg(c)?),
+                (Operation::Shl, Mode::RegReg) => Code::ShlR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Shl, _) => Code::ShlI(reg(a)?, b, reg(c)?),
+                (Operation::Shr, Mode::RegReg) => Code::ShrR(reg(a)?, reg(b)?, reg(c)?),
+                (Operation::Shr, _) => Code::ShrI(reg(a)?, b, reg(c)?),
+            };
+            Ok(code)
+        }).collect::<Result<Vec<_>>>()?;
+
+        Ok(Compiled {
+            code,
+            ip_register: program.ip_register.map(reg).transpose()?,
+            registers,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.code.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.code.is_empty()
+    }
+
+    /// Run the program on the machine until it halts, starting from the
+    /// machine's current instruction pointer.  This leaves the machine in
+    /// the same state the interpreter would, including the step count,
+    /// and stops with the same errors, at the step limit or when the
+    /// overflow policy makes an error.
+    pub fn run(&self, machine: &mut Machine) -> Result<()> {
+        if machine.regs.len() < self.registers {
+            return Err(Error::BadRegister {
+                register: self.registers as Word - 1,
+                count: machine.regs.len(),
+            });
+        }
+
+        let mask = machine.mask();
+        let overflow = machine.overflow;
+        let limit = machine.limit.unwrap_or(u64::MAX);
+        let mut ip = machine.ip;
+        let mut steps = machine.steps;
+        let regs = &mut machine.regs[..];
+
+        // Having a separate loop for each kind of program keeps the check
+        // for the instruction pointer binding out of the loop.
+        let result = match self.ip_register {
+            None => loop {
+                let code = match self.code.get(ip) {
+                    Some(code) => code,
+                    None => break Ok(()),
+                };
+                if steps == limit {
+                    break Err(Error::StepLimit(steps));
+                }
+                if let Err(err) = code.exec(regs, mask, overflow) {
+                    break Err(err);
+                }
+                ip += 1;
+                steps += 1;
+            },
+            Some(r) => loop {
+                let code = match self.code.get(ip) {
+                    Some(code) => code,
+                    None => break Ok(()),
+                };
+                if steps == limit {
+                    break Err(Error::StepLimit(steps));
+                }
+                regs[r] = ip as Word & mask;
+                if let Err(err) = code.exec(regs, mask, overflow) {
+                    break Err(err);
+                }
+                // A value too big for the instruction pointer is well
+                // outside of the program, so halt there.
+                ip = (regs[r] as usize).saturating_add(1);
+                steps += 1;
+            },
+        };
+
+        machine.ip = ip;
+        machine.steps = steps;
+        result
+    }
+}
+
+impl Code {
+    /// Run the instruction.
+    #[inline(always)]
+    fn exec(self, regs: &mut [Word], mask: Word, overflow: Overflow) -> Result<()> {
+        match self {
+            Code::AddR(a, b, c) => regs[c] = self.arith(regs[a], regs[b], mask, overflow.add(regs[a], regs[b], mask))?,
+            Code::AddI(a, b, c) => regs[c] = self.arith(regs[a], b, mask, overflow.add(regs[a], b, mask))?,
+            Code::MulR(a, b, c) => regs[c] = self.arith(regs[a], regs[b], mask, overflow.mul(regs[a], regs[b], mask))?,
+            Code::MulI(a, b, c) => regs[c] = self.arith(regs[a], b, mask, overflow.mul(regs[a], b, mask))?,
+            Code::BanR(a, b, c) => regs[c] = regs[a] & regs[b],
+            Code::BanI(a, b, c) => regs[c] = regs[a] & b & mask,
+            Code::BorR(a, b, c) => regs[c] = regs[a] | regs[b],
+            Code::BorI(a, b, c) => regs[c] = (regs[a] | b) & mask,
+            Code::SetR(a, c) => regs[c] = regs[a],
+            Code::SetI(a, c) => regs[c] = a & mask,
+            Code::GtIR(a, b, c) => regs[c] = (a > regs[b]) as Word,
+            Code::GtRI(a, b, c) => regs[c] = (regs[a] > b) as Word,
+            Code::GtRR(a, b, c) => regs[c] = (regs[a] > regs[b]) as Word,
+            Code::EqIR(a, b, c) => regs[c] = (a == regs[b]) as Word,
+            Code::EqRI(a, b, c) => regs[c] = (regs[a] == b) as Word,
+            Code::EqRR(a, b, c) => regs[c] = (regs[a] == regs[b]) as Word,
+            Code::DivR(a, b, c) => regs[c] = self.divide(regs[a], regs[a].checked_div(regs[b]))?,
+            Code::DivI(a, b, c) => regs[c] = self.divide(regs[a], regs[a].checked_div(b))?,
+            Code::ModR(a, b, c) => regs[c] = self.divide(regs[a], regs[a].checked_rem(regs[b]))?,
+            Code::ModI(a, b, c) => regs[c] = self.divide(regs[a], regs[a].checked_rem(b))?,
+            Code::ShlR(a, b, c) => regs[c] = shl(regs[a], regs[b]) & mask,
+            Code::ShlI(a, b, c) => regs[c] = shl(regs[a], b) & mask,
+            Code::ShrR(a, b, c) => regs[c] = shr(regs[a], regs[b]),
+            Code::ShrI(a, b, c) => regs[c] = shr(regs[a], b),
+        }
+        Ok(())
+    }
+
+    /// The result of an addition or multiplication, or the error if the
+    /// overflow policy made it one.
+    #[inline(always)]
+    fn arith(self, a: Word, b: Word, mask: Word, result: Option<Word>) -> Result<Word> {
+        result.ok_or_else(|| Error::Overflow { op: self.opcode(), a, b, width: mask.count_ones() })
+    }
+
+    /// The result of a division or remainder, or the error if it was by
+    /// zero.
+    #[inline(always)]
+    fn divide(self, a: Word, result: Option<Word>) -> Result<Word> {
+        result.ok_or_else(|| Error::DivideByZero { op: self.opcode(), a })
+    }
+
+    /// The opcode this came from, for errors.
+    fn opcode(self) -> Opcode {
+        match self {
+            Code::AddR(..) => Opcode::Addr,
+            Code::AddI(..) => Opcode::Addi,
+            Code::MulR(..) => Opcode::Mulr,
+            Code::MulI(..) => Opcode::Muli,
+            Code::BanR(..) => Opcode::Banr,
+            Code::BanI(..) => Opcode::Bani,
+            Code::BorR(..) => Opcode::Borr,
+            Code::BorI(..) => Opcode::Bori,
+            Code::SetR(..) => Opcode::Setr,
+            Code::SetI(..) => Opcode::Seti,
+            Code::GtIR(..) => Opcode::Gtir,
+            Code::GtRI(..) => Opcode::Gtri,
+            Code::GtRR(..) => Opcode::Gtrr,
+            Code::EqIR(..) => Opcode::Eqir,
+            Code::EqRI(..) => Opcode::Eqri,
+            Code::EqRR(..) => Opcode::Eqrr,
+            Code::DivR(..) => Opcode::Divr,
+            Code::DivI(..) => Opcode::Divi,
+            Code::ModR(..) => Opcode::Modr,
+            Code::ModI(..) => Opcode::Modi,
+            Code::ShlR(..) => Opcode::Shlr,
+            Code::ShlI(..) => Opcode::Shli,
+            Code::ShrR(..) => Opcode::Shrr,
+            Code::ShrI(..) => Opcode::Shri,
+        }
+    }
+}
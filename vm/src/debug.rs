@@ -0,0 +1,211 @@
+//! Support for debugging programs: breakpoints, watchpoints, and stepping
+//! backward through the program's history.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+};
+use crate::{
+    Machine,
+    Opcode,
+    Program,
+    Result,
+    Word,
+};
+
+/// How many previous states are kept for stepping backward.
+const HISTORY: usize = 100_000;
+
+/// Where to stop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Before running the instruction at this address.
+    Ip(usize),
+
+    /// Before running any instruction with this opcode.
+    Opcode(Opcode),
+}
+
+/// Why the program stopped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    /// A single step finished.
+    Step,
+
+    /// The next instruction is at a breakpoint.
+    Breakpoint(Breakpoint),
+
+    /// A watched register changed.
+    Watch {
+        register: Word,
+        old: Word,
+        new: Word,
+    },
+
+    /// The instruction pointer left the program.
+    Halted,
+}
+
+/// A program being run under the debugger.
+pub struct Debugger {
+    program: Program,
+    machine: Machine,
+    breakpoints: Vec<Breakpoint>,
+    watches: Vec<Word>,
+
+    /// The machine state before each of the recent steps, most recent at
+    /// the back.
+    history: VecDeque<Machine>,
+}
+
+impl Debugger {
+    pub fn new(program: Program, machine: Machine) -> Debugger {
+        Debugger {
+            program,
+            machine,
+            breakpoints: vec![],
+            watches: vec![],
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn program(&self) -> &Program {
+        &self.program
+    }
+
+    pub fn machine(&self) -> &Machine {
+        &self.machine
+    }
+
+    /// Change the machine directly, such as to set a register.
+    pub fn machine_mut(&mut self) -> &mut Machine {
+        &mut self.machine
+    }
+
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    pub fn add_breakpoint(&mut self, bp: Breakpoint) {
+        if !self.breakpoints.contains(&bp) {
+            self.breakpoints.push(bp);
+        }
+    }
+
+    /// Remove a breakpoint, returning whether it was set.
+    pub fn remove_breakpoint(&mut self, bp: Breakpoint) -> bool {
+        let before = self.breakpoints.len();
+        self.breakpoints.retain(|&b| b != bp);
+        self.breakpoints.len() != before
+    }
+
+    pub fn watches(&self) -> &[Word] {
+        &self.watches
+    }
+
+    /// Stop whenever the given register changes.
+    pub fn watch(&mut self, register: Word) -> Result<()> {
+        self.machine.register(register)?;
+        if !self.watches.contains(&register) {
+            self.watches.push(register);
+        }
+        Ok(())
+    }
+
+    pub fn unwatch(&mut self, register: Word) -> bool {
+        let before = self.watches.len();
+        self.watches.retain(|&r| r != register);
+        self.watches.len() != before
+    }
+
+    /// Is the program finished?
+    pub fn halted(&self) -> bool {
+        self.machine.ip() >= self.program.code.len()
+    }
+
+    /// Run a single instruction.
+    pub fn step(&mut self) -> Result<Stop> {
+        if self.halted() {
+            return Ok(Stop::Halted);
+        }
+
+        let before = self.machine.clone();
+        self.machine.step(&self.program)?;
+        if self.history.len() == HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(before);
+
+        // The instruction pointer's register always changes, so only
+        // report changes in it when they are jumps.
+        let last = &self.history[self.history.len() - 1];
+        for &register in &self.watches {
+            let old = last.registers()[register as usize];
+            let new = self.machine.registers()[register as usize];
+            if old != new && !(self.program.ip_register == Some(register) && self.is_fallthrough(last)) {
+                return Ok(Stop::Watch { register, old, new });
+            }
+        }
+
+        if self.halted() {
+            Ok(Stop::Halted)
+        } else {
+            Ok(Stop::Step)
+        }
+    }
+
+    /// Did the last step just move on to the next instruction?
+    fn is_fallthrough(&self, before: &Machine) -> bool {
+        self.machine.ip() == before.ip() + 1
+    }
+
+    /// Run until the program halts, reaches a breakpoint, or changes a
+    /// watched register.  The instruction at the current position always
+    /// runs, so that continuing from a breakpoint makes progress.
+    pub fn cont(&mut self) -> Result<Stop> {
+        loop {
+            match self.step()? {
+                Stop::Step => (),
+                stop => return Ok(stop),
+            }
+            if let Some(bp) = self.breakpoint_here() {
+                return Ok(Stop::Breakpoint(bp));
+            }
+        }
+    }
+
+    /// The breakpoint the next instruction is at, if any.
+    fn breakpoint_here(&self) -> Option<Breakpoint> {
+        let ip = self.machine.ip();
+        let instr = self.program.code.get(ip)?;
+        self.breakpoints.iter().cloned().find(|&bp| match bp {
+            Breakpoint::Ip(addr) => addr == ip,
+            Breakpoint::Opcode(op) => op == instr.op,
+        })
+    }
+
+    /// Undo the last step.  Returns false if there is no more history.
+    pub fn back(&mut self) -> bool {
+        match self.history.pop_back() {
+            Some(machine) => {
+                self.machine = machine;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// How many steps can be undone.
+    pub fn history_len(&self) -> usize {
+        self.history.len()
+    }
+}
+
+impl fmt::Display for Breakpoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Breakpoint::Ip(addr) => write!(f, "ip {}", addr),
+            Breakpoint::Opcode(op) => write!(f, "opcode {}", op.name()),
+        }
+    }
+}
@@ -0,0 +1,729 @@
+//! Turning programs back into something readable.
+//!
+//! Each instruction becomes an assignment of an expression to a register.
+//! When the instruction pointer is bound to a register, reading it gives
+//! the address of the instruction, and writing it is a jump, so the program
+//! is split into basic blocks joined by jumps, forming a control-flow graph.
+//! Values that are only used once, by a later instruction in the same
+//! block, are folded into the expression that uses them, which turns most
+//! of the compare-and-jump sequences back into conditional jumps.  Loops
+//! are found from the jumps backward to a block that must have run first.
+
+use std::{
+    collections::BTreeSet,
+    fmt,
+};
+use crate::{
+    Instruction,
+    Operation,
+    Program,
+    Word,
+    machine::{shl, shr},
+};
+
+/// A value computed from registers and constants.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr {
+    Reg(Word),
+    Const(Word),
+
+    /// Any operation but `Set`, which is just its argument.
+    Binary(Operation, Box<Expr>, Box<Expr>),
+}
+
+/// An assignment to a register.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Assign {
+    /// The address of the instruction this came from.
+    pub addr: usize,
+    pub reg: Word,
+    pub expr: Expr,
+}
+
+/// How a block ends.  Targets are addresses, and a target outside of the
+/// program halts it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Exit {
+    Goto(usize),
+    Branch {
+        cond: Expr,
+        taken: usize,
+        not_taken: usize,
+    },
+
+    /// A jump to one more than the value of the expression, which can't be
+    /// worked out ahead of time.
+    Computed(Expr),
+
+    /// The end of the program.
+    Halt,
+}
+
+/// A run of instructions that is only entered at the top, and only left at
+/// the bottom.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block {
+    /// The addresses of the instructions in the block, `end` being one
+    /// past the last.
+    pub start: usize,
+    pub end: usize,
+    pub stmts: Vec<Assign>,
+    pub exit: Exit,
+    pub reachable: bool,
+}
+
+/// A loop, found from one or more jumps back to its header.  Blocks are
+/// given by their index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Loop {
+    pub header: usize,
+
+    /// The blocks that jump back to the header.
+    pub latches: Vec<usize>,
+
+    /// Every block in the loop, including the header, in order.
+    pub body: Vec<usize>,
+}
+
+/// A decompiled program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Decompiled {
+    pub ip_register: Option<Word>,
+    pub blocks: Vec<Block>,
+    pub loops: Vec<Loop>,
+
+    /// The length of the program, so targets past it can be shown as
+    /// halting.
+    len: usize,
+}
+
+/// Decompile a program.
+pub fn decompile(program: &Program) -> Decompiled {
+    let len = program.code.len();
+    let ip = program.ip_register;
+
+    // Each instruction either assigns to a register, or jumps.
+    let raw: Vec<(Option<Assign>, Option<Expr>)> = program.code.iter().enumerate().map(|(addr, instr)| {
+        let expr = instr_expr(instr, addr, ip);
+        if Some(instr.c) == ip {
+            (None, Some(expr))
+        } else {
+            (Some(Assign { addr, reg: instr.c, expr }), None)
+        }
+    }).collect();
+
+    // A new block starts at the start, after each jump, and at anywhere
+    // that might be jumped to.
+    let mut leaders = BTreeSet::new();
+    leaders.insert(0);
+    for (addr, (_, jump)) in raw.iter().enumerate() {
+        if let Some(jump) = jump {
+            leaders.insert(addr + 1);
+            match jump {
+                Expr::Const(k) => {
+                    leaders.insert(target(*k));
+                }
+                _ if relative_reg(jump, addr).is_some() => {
+                    leaders.insert(addr + 2);
+                }
+                _ => (),
+            }
+        }
+    }
+    let leaders: Vec<usize> = leaders.into_iter().filter(|&a| a < len).collect();
+
+    let mut blocks: Vec<Block> = leaders.iter().enumerate().map(|(i, &start)| {
+        let end = leaders.get(i + 1).cloned().unwrap_or(len);
+        let stmts: Vec<Assign> = raw[start .. end].iter().filter_map(|(a, _)| a.clone()).collect();
+        let exit = match &raw[end - 1].1 {
+            None if end == len => Exit::Halt,
+            None => Exit::Goto(end),
+            Some(jump) => jump_exit(jump, end - 1, &stmts),
+        };
+        Block { start, end, stmts, exit, reachable: false }
+    }).collect();
+
+    let succs: Vec<Vec<usize>> = blocks.iter()
+        .map(|b| b.targets().into_iter().filter_map(|t| leaders.binary_search(&t).ok()).collect())
+        .collect();
+
+    // A computed jump could go anywhere.
+    let mut work = if blocks.is_empty() { vec![] } else { vec![0] };
+    while let Some(i) = work.pop() {
+        if blocks[i].reachable {
+            continue;
+        }
+        blocks[i].reachable = true;
+        if let Exit::Computed(_) = blocks[i].exit {
+            work.extend(0 .. blocks.len());
+        }
+        work.extend(&succs[i]);
+    }
+
+    let live_out = liveness(&blocks, &succs, program.register_count(), len);
+    for (block, live) in blocks.iter_mut().zip(&live_out) {
+        simplify(block, live);
+    }
+
+    let loops = find_loops(&blocks, &succs);
+    Decompiled { ip_register: ip, blocks, loops, len }
+}
+
+/// The expression an instruction computes.  Reading the instruction
+/// pointer's register gives the instruction's own address.
+fn instr_expr(instr: &Instruction, addr: usize, ip: Option<Word>) -> Expr {
+    let arg = |value: Word, is_register: bool| {
+        if !is_register {
+            Expr::Const(value)
+        } else if Some(value) == ip {
+            Expr::Const(addr as Word)
+        } else {
+            Expr::Reg(value)
+        }
+    };
+    let a = arg(instr.a, instr.op.a_is_register());
+    match instr.op.operation() {
+        Operation::Set => a,
+        op => Expr::Binary(op, Box::new(a), Box::new(arg(instr.b, instr.op.b_is_register()))).fold(),
+    }
+}
+
+/// The address after an instruction pointer value, which is where a jump
+/// goes.
+fn target(value: Word) -> usize {
+    (value as usize).saturating_add(1)
+}
+
+/// If the jump adds a register to the instruction's own address, which is
+/// how a conditional jump is done, return the register.
+fn relative_reg(jump: &Expr, addr: usize) -> Option<Word> {
+    match jump {
+        Expr::Binary(Operation::Add, a, b) => match (&**a, &**b) {
+            (Expr::Const(k), Expr::Reg(r)) | (Expr::Reg(r), Expr::Const(k)) if *k == addr as Word => Some(*r),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// How a block ending with a jump at `addr` exits.
+fn jump_exit(jump: &Expr, addr: usize, stmts: &[Assign]) -> Exit {
+    if let Expr::Const(k) = jump {
+        return Exit::Goto(target(*k));
+    }
+    // Adding a register that was just set by a comparison either skips
+    // the next instruction or doesn't.
+    if let Some(r) = relative_reg(jump, addr) {
+        if stmts.iter().rev().find(|s| s.reg == r).is_some_and(|s| s.expr.is_boolean()) {
+            return Exit::Branch {
+                cond: Expr::Reg(r),
+                taken: addr + 2,
+                not_taken: addr + 1,
+            };
+        }
+    }
+    Exit::Computed(jump.clone())
+}
+
+/// Which registers are live at the end of each block.  Everything is live
+/// when the program halts, including by jumping past its end, since the
+/// registers are its result, and after a computed jump, since it could go
+/// anywhere.
+fn liveness(blocks: &[Block], succs: &[Vec<usize>], registers: usize, len: usize) -> Vec<Vec<bool>> {
+    let all = vec![true; registers];
+    let mut live_in = vec![vec![false; registers]; blocks.len()];
+    let mut live_out = vec![vec![false; registers]; blocks.len()];
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in (0 .. blocks.len()).rev() {
+            let block = &blocks[i];
+            let mut out = match &block.exit {
+                Exit::Halt | Exit::Computed(_) => all.clone(),
+                _ if block.targets().iter().any(|&t| t >= len) => all.clone(),
+                _ => vec![false; registers],
+            };
+            for &s in &succs[i] {
+                for (o, &l) in out.iter_mut().zip(&live_in[s]) {
+                    *o |= l;
+                }
+            }
+
+            // Work backward through the block.
+            let mut live = out.clone();
+            if let Some(expr) = block.exit_expr() {
+                expr.mark(&mut live);
+            }
+            for stmt in block.stmts.iter().rev() {
+                live[stmt.reg as usize] = false;
+                stmt.expr.mark(&mut live);
+            }
+
+            if live != live_in[i] || out != live_out[i] {
+                live_in[i] = live;
+                live_out[i] = out;
+                changed = true;
+            }
+        }
+    }
+    live_out
+}
+
+/// Fold values that are only used once into the expression using them,
+/// and drop assignments whose values are never used.
+fn simplify(block: &mut Block, live_out: &[bool]) {
+    'again: loop {
+        for i in 0 .. block.stmts.len() {
+            let reg = block.stmts[i].reg;
+            let next_def = (i + 1 .. block.stmts.len()).find(|&j| block.stmts[j].reg == reg);
+            let dead_after = next_def.is_some() || !live_out[reg as usize];
+            if !dead_after {
+                continue;
+            }
+
+            // The uses of this value, which go up to and including the
+            // next assignment to the register.  `None` is the exit.
+            let last = next_def.map_or(block.stmts.len(), |j| j + 1);
+            let mut uses: Vec<(Option<usize>, usize)> = (i + 1 .. last)
+                .map(|j| (Some(j), block.stmts[j].expr.uses(reg)))
+                .filter(|&(_, n)| n > 0)
+                .collect();
+            if next_def.is_none() {
+                if let Some(expr) = block.exit_expr() {
+                    let n = expr.uses(reg);
+                    if n > 0 {
+                        uses.push((None, n));
+                    }
+                }
+            }
+
+            match uses[..] {
+                [] => {
+                    block.stmts.remove(i);
+                    continue 'again;
+                }
+                [(at, 1)] => {
+                    // The registers the value is computed from mustn't
+                    // change before it is used.
+                    let end = at.unwrap_or(block.stmts.len());
+                    let expr = block.stmts[i].expr.clone();
+                    if (i .. end).any(|j| expr.uses(block.stmts[j].reg) > 0) {
+                        continue;
+                    }
+                    match at {
+                        Some(j) => block.stmts[j].expr = block.stmts[j].expr.substitute(reg, &expr),
+                        None => block.substitute_exit(reg, &expr),
+                    }
+                    block.stmts.remove(i);
+                    continue 'again;
+                }
+                _ => (),
+            }
+        }
+        break;
+    }
+}
+
+/// Find the loops, from jumps to a block that dominates the one jumping.
+fn find_loops(blocks: &[Block], succs: &[Vec<usize>]) -> Vec<Loop> {
+    let n = blocks.len();
+    let mut preds = vec![vec![]; n];
+    for (i, ss) in succs.iter().enumerate() {
+        for &s in ss {
+            preds[s].push(i);
+        }
+    }
+
+    // The blocks that must run before each one, starting with all of them.
+    let mut dom = vec![vec![true; n]; n];
+    if n > 0 {
+        dom[0] = (0 .. n).map(|i| i == 0).collect();
+    }
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 1 .. n {
+            let mut d = vec![true; n];
+            let mut any = false;
+            for &p in preds[i].iter().filter(|&&p| blocks[p].reachable) {
+                any = true;
+                for (x, &y) in d.iter_mut().zip(&dom[p]) {
+                    *x &= y;
+                }
+            }
+            if !any {
+                d = vec![false; n];
+            }
+            d[i] = true;
+            if d != dom[i] {
+                dom[i] = d;
+                changed = true;
+            }
+        }
+    }
+
+    let mut loops: Vec<Loop> = vec![];
+    for (latch, ss) in succs.iter().enumerate() {
+        for &header in ss {
+            if !blocks[latch].reachable || !dom[latch][header] {
+                continue;
+            }
+
+            // The body is everything that reaches the latch without going
+            // through the header.
+            let mut body = BTreeSet::new();
+            body.insert(header);
+            let mut work = vec![latch];
+            while let Some(b) = work.pop() {
+                if body.insert(b) {
+                    work.extend(&preds[b]);
+                }
+            }
+
+            match loops.iter_mut().find(|l| l.header == header) {
+                Some(l) => {
+                    l.latches.push(latch);
+                    let merged: BTreeSet<usize> = l.body.iter().cloned().chain(body).collect();
+                    l.body = merged.into_iter().collect();
+                }
+                None => loops.push(Loop {
+                    header,
+                    latches: vec![latch],
+                    body: body.into_iter().collect(),
+                }),
+            }
+        }
+    }
+    loops.sort_by_key(|l| l.header);
+    loops
+}
+
+impl Expr {
+    /// Work out operations on constants.  Additions and multiplications
+    /// that would overflow are left alone, since the result depends on the
+    /// machine.
+    fn fold(self) -> Expr {
+        if let Expr::Binary(op, a, b) = &self {
+            if let (Expr::Const(a), Expr::Const(b)) = (&**a, &**b) {
+                let value = match op {
+                    Operation::Add => a.checked_add(*b),
+                    Operation::Mul => a.checked_mul(*b),
+                    Operation::Ban => Some(a & b),
+                    Operation::Bor => Some(a | b),
+                    Operation::Set => Some(*a),
+                    Operation::Gt => Some((a > b) as Word),
+                    Operation::Eq => Some((a == b) as Word),
+                    Operation::Div => a.checked_div(*b),
+                    Operation::Mod => a.checked_rem(*b),
+                    // Bits shifted out might be kept by a wider machine.
+                    Operation::Shl => Some(shl(*a, *b)).filter(|&v| shr(v, *b) == *a),
+                    Operation::Shr => Some(shr(*a, *b)),
+                };
+                if let Some(value) = value {
+                    return Expr::Const(value);
+                }
+            }
+        }
+        self
+    }
+
+    /// The value of the expression, with the given register values, on a
+    /// machine with 64-bit words and wrapping arithmetic.  This is `None`
+    /// if it divides by zero.
+    pub fn eval(&self, regs: &[Word]) -> Option<Word> {
+        match self {
+            Expr::Reg(r) => Some(regs[*r as usize]),
+            Expr::Const(value) => Some(*value),
+            Expr::Binary(op, a, b) => {
+                let (a, b) = (a.eval(regs)?, b.eval(regs)?);
+                match op {
+                    Operation::Add => Some(a.wrapping_add(b)),
+                    Operation::Mul => Some(a.wrapping_mul(b)),
+                    Operation::Ban => Some(a & b),
+                    Operation::Bor => Some(a | b),
+                    Operation::Set => Some(a),
+                    Operation::Gt => Some((a > b) as Word),
+                    Operation::Eq => Some((a == b) as Word),
+                    Operation::Div => a.checked_div(b),
+                    Operation::Mod => a.checked_rem(b),
+                    Operation::Shl => Some(shl(a, b)),
+                    Operation::Shr => Some(shr(a, b)),
+                }
+            }
+        }
+    }
+
+    /// Is this always 0 or 1?
+    pub fn is_boolean(&self) -> bool {
+        match self {
+            Expr::Binary(Operation::Gt, ..) | Expr::Binary(Operation::Eq, ..) => true,
+            Expr::Const(value) => *value <= 1,
+            _ => false,
+        }
+    }
+
+    /// How many times the register is read.
+    fn uses(&self, reg: Word) -> usize {
+        match self {
+            Expr::Reg(r) => (*r == reg) as usize,
+            Expr::Const(_) => 0,
+            Expr::Binary(_, a, b) => a.uses(reg) + b.uses(reg),
+        }
+    }
+
+    /// Mark the registers this reads.
+    fn mark(&self, live: &mut [bool]) {
+        match self {
+            Expr::Reg(r) => live[*r as usize] = true,
+            Expr::Const(_) => (),
+            Expr::Binary(_, a, b) => {
+                a.mark(live);
+                b.mark(live);
+            }
+        }
+    }
+
+    /// Replace reads of the register with the given expression.
+    fn substitute(&self, reg: Word, with: &Expr) -> Expr {
+        match self {
+            Expr::Reg(r) if *r == reg => with.clone(),
+            Expr::Binary(op, a, b) => {
+                Expr::Binary(*op, Box::new(a.substitute(reg, with)), Box::new(b.substitute(reg, with))).fold()
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// How tightly the operator binds, following Rust.
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Binary(Operation::Mul, ..) | Expr::Binary(Operation::Div, ..) | Expr::Binary(Operation::Mod, ..) => 6,
+            Expr::Binary(Operation::Add, ..) => 5,
+            Expr::Binary(Operation::Shl, ..) | Expr::Binary(Operation::Shr, ..) => 4,
+            Expr::Binary(Operation::Ban, ..) => 3,
+            Expr::Binary(Operation::Bor, ..) => 2,
+            Expr::Binary(..) => 1,
+            _ => 7,
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Reg(r) => write!(f, "r{}", r),
+            Expr::Const(value) => write!(f, "{}", value),
+            Expr::Binary(op, a, b) => {
+                let sym = match op {
+                    Operation::Add => "+",
+                    Operation::Mul => "*",
+                    Operation::Ban => "&",
+                    Operation::Bor => "|",
+                    Operation::Set => "=",
+                    Operation::Gt => ">",
+                    Operation::Eq => "==",
+                    Operation::Div => "/",
+                    Operation::Mod => "%",
+                    Operation::Shl => "<<",
+                    Operation::Shr => ">>",
+                };
+                // Comparisons don't chain, and the right side needs
+                // parentheses at the same level to keep its grouping.
+                let prec = self.precedence();
+                let left = a.precedence() < prec || (prec == 1 && a.precedence() == 1);
+                let right = b.precedence() <= prec;
+                let side = |f: &mut fmt::Formatter, e: &Expr, paren: bool| {
+                    if paren { write!(f, "({})", e) } else { write!(f, "{}", e) }
+                };
+                side(f, a, left)?;
+                write!(f, " {} ", sym)?;
+                side(f, b, right)
+            }
+        }
+    }
+}
+
+impl Block {
+    /// The addresses this block can go to next, which may be outside of
+    /// the program.
+    pub fn targets(&self) -> Vec<usize> {
+        match &self.exit {
+            Exit::Goto(t) => vec![*t],
+            Exit::Branch { taken, not_taken, .. } => vec![*taken, *not_taken],
+            Exit::Computed(_) | Exit::Halt => vec![],
+        }
+    }
+
+    fn exit_expr(&self) -> Option<&Expr> {
+        match &self.exit {
+            Exit::Branch { cond, .. } => Some(cond),
+            Exit::Computed(expr) => Some(expr),
+            _ => None,
+        }
+    }
+
+    fn substitute_exit(&mut self, reg: Word, with: &Expr) {
+        match &mut self.exit {
+            Exit::Branch { cond, .. } => *cond = cond.substitute(reg, with),
+            Exit::Computed(expr) => *expr = expr.substitute(reg, with),
+            _ => (),
+        }
+    }
+}
+
+impl Decompiled {
+    /// Where a jump to the given address goes.
+    fn jump(&self, target: usize) -> String {
+        if target >= self.len {
+            "halt".to_string()
+        } else {
+            format!("goto L{}", target)
+        }
+    }
+
+    /// The lines describing how a block exits.  `next` is where falling
+    /// through goes.
+    fn exit_lines(&self, block: &Block, next: Option<usize>) -> Vec<String> {
+        match &block.exit {
+            Exit::Goto(t) if Some(*t) == next => vec![],
+            Exit::Goto(t) => vec![self.jump(*t)],
+            Exit::Branch { cond, taken, not_taken } => {
+                let mut lines = vec![format!("if {} {}", cond, self.jump(*taken))];
+                if Some(*not_taken) != next {
+                    lines.push(self.jump(*not_taken));
+                }
+                lines
+            }
+            Exit::Computed(expr) => vec![format!("goto {} + 1", Paren(expr))],
+            Exit::Halt => vec!["halt".to_string()],
+        }
+    }
+
+    /// The program as pseudocode.  Loops whose blocks are all together
+    /// are shown as indented `loop` blocks, with the jumps still shown.
+    pub fn pseudocode(&self) -> Vec<String> {
+        let mut lines = vec![];
+        match self.ip_register {
+            Some(r) => lines.push(format!("; ip bound to r{}", r)),
+            None => lines.push("; no ip binding, runs straight through".to_string()),
+        }
+
+        // Only loops that nest properly can be shown as blocks.
+        let mut spans: Vec<(usize, usize, &Loop)> = self.loops.iter()
+            .filter(|l| l.body.len() == l.body[l.body.len() - 1] - l.body[0] + 1)
+            .map(|l| (l.body[0], l.body[l.body.len() - 1], l))
+            .collect();
+        spans.sort_by_key(|&(first, last, _)| (first, usize::MAX - last));
+        let mut nested: Vec<(usize, usize, &Loop)> = vec![];
+        for span in spans {
+            let fits = nested.iter().all(|&(f, l, _)| span.1 < f || span.0 > l ||
+                                         (span.0 >= f && span.1 <= l));
+            if fits {
+                nested.push(span);
+            }
+        }
+
+        let mut depth = 0;
+        for (i, block) in self.blocks.iter().enumerate() {
+            for &(_, _, l) in nested.iter().filter(|&&(first, _, _)| first == i) {
+                let latches: Vec<String> = l.latches.iter()
+                    .map(|&b| format!("L{}", self.blocks[b].start))
+                    .collect();
+                lines.push(format!("{}loop {{  ; back from {}", indent(depth), latches.join(", ")));
+                depth += 1;
+            }
+
+            let note = if block.reachable { "" } else { "  ; unreachable" };
+            lines.push(format!("{}L{}:{}", indent(depth), block.start, note));
+            for stmt in &block.stmts {
+                lines.push(format!("{}r{} = {}", indent(depth + 1), stmt.reg, stmt.expr));
+            }
+            let next = self.blocks.get(i + 1).map(|b| b.start);
+            for line in self.exit_lines(block, next) {
+                lines.push(format!("{}{}", indent(depth + 1), line));
+            }
+
+            for _ in nested.iter().filter(|&&(_, last, _)| last == i) {
+                depth -= 1;
+                lines.push(format!("{}}}", indent(depth)));
+            }
+        }
+        lines
+    }
+
+    /// The control-flow graph, in Graphviz's dot language.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::new();
+        out.push_str("digraph cfg {\n");
+        out.push_str("    node [shape=box, fontname=monospace];\n");
+        let mut halts = false;
+        for (i, block) in self.blocks.iter().enumerate() {
+            let mut label = format!("L{}:\\l", block.start);
+            for stmt in &block.stmts {
+                label.push_str(&format!("r{} = {}\\l", stmt.reg, stmt.expr));
+            }
+            for line in self.exit_lines(block, None) {
+                label.push_str(&line);
+                label.push_str("\\l");
+            }
+            let style = if block.reachable { "" } else { ", style=dashed" };
+            out.push_str(&format!("    b{} [label=\"{}\"{}];\n", i, label.replace('"', "\\\""), style));
+
+            let edge = |out: &mut String, halts: &mut bool, target: usize, label: &str| {
+                let back = self.loops.iter().any(|l| l.latches.contains(&i) && self.blocks[l.header].start == target);
+                let mut attrs = vec![];
+                if !label.is_empty() {
+                    attrs.push(format!("label=\"{}\"", label));
+                }
+                if back {
+                    attrs.push("color=red".to_string());
+                }
+                let attrs = if attrs.is_empty() { String::new() } else { format!(" [{}]", attrs.join(", ")) };
+                let node = match self.blocks.iter().position(|b| b.start == target) {
+                    Some(t) => format!("b{}", t),
+                    None => {
+                        *halts = true;
+                        "halt".to_string()
+                    }
+                };
+                out.push_str(&format!("    b{} -> {}{};\n", i, node, attrs));
+            };
+            match &block.exit {
+                Exit::Goto(t) => edge(&mut out, &mut halts, *t, ""),
+                Exit::Branch { taken, not_taken, .. } => {
+                    edge(&mut out, &mut halts, *taken, "true");
+                    edge(&mut out, &mut halts, *not_taken, "false");
+                }
+                Exit::Computed(_) => out.push_str(&format!("    b{} -> computed [style=dashed];\n", i)),
+                Exit::Halt => {
+                    halts = true;
+                    out.push_str(&format!("    b{} -> halt;\n", i));
+                }
+            }
+        }
+        if halts {
+            out.push_str("    halt [shape=doublecircle];\n");
+        }
+        if self.blocks.iter().any(|b| matches!(b.exit, Exit::Computed(_))) {
+            out.push_str("    computed [shape=diamond, label=\"?\"];\n");
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn indent(depth: usize) -> String {
+    "    ".repeat(depth)
+}
+
+/// An expression in parentheses, unless it is a single value.
+struct Paren<'a>(&'a Expr);
+
+impl<'a> fmt::Display for Paren<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Expr::Binary(..) => write!(f, "({})", self.0),
+            _ => write!(f, "{}", self.0),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! Errors from running the machine.
+
+use std::{
+    error,
+    fmt,
+    result,
+};
+use crate::{Opcode, Operation, Word};
+
+pub type Result<T> = result::Result<T, Error>;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// An instruction referred to a register that the machine doesn't
+    /// have.
+    BadRegister {
+        register: Word,
+        count: usize,
+    },
+
+    /// A numeric opcode that isn't in the opcode map.
+    BadOpcode(Word),
+
+    /// An opcode that has no number in the opcode map.
+    Unmapped(Opcode),
+
+    /// An opcode given more than once when building an instruction set.
+    DuplicateOpcode(Opcode),
+
+    /// The program ran for more steps than the machine's limit.
+    StepLimit(u64),
+
+    /// An addition or multiplication didn't fit in a word, and the
+    /// machine's overflow policy makes that an error.
+    Overflow {
+        op: Opcode,
+        a: Word,
+        b: Word,
+        width: u32,
+    },
+
+    /// A division or remainder by zero.
+    DivideByZero {
+        op: Opcode,
+        a: Word,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::BadRegister { register, count } => {
+                write!(f, "Register {} out of range, machine has {} registers", register, count)
+            }
+            Error::BadOpcode(code) => write!(f, "Unknown opcode {}", code),
+            Error::Unmapped(op) => write!(f, "Opcode {} has no number", op.name()),
+            Error::DuplicateOpcode(op) => write!(f, "Opcode {} is in the instruction set twice", op.name()),
+            Error::StepLimit(limit) => write!(f, "Program still running after {} steps", limit),
+            Error::Overflow { op, a, b, width } => {
+                let sign = if op.operation() == Operation::Mul { "*" } else { "+" };
+                write!(f, "Overflow in {}: {} {} {} doesn't fit in {} bits", op.name(), a, sign, b, width)
+            }
+            Error::DivideByZero { op, a } => write!(f, "Division by zero in {}: {} by 0", op.name(), a),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<Error> for common::Error {
+    fn from(err: Error) -> common::Error {
+        common::Error::Other(Box::new(err))
+    }
+}
@@ -0,0 +1,33 @@
+//! The wrist device's register machine, from day 16.
+//!
+//! The machine has a set of registers, and sixteen opcodes, plus a few
+//! extensions the device doesn't have.  Each opcode takes three arguments,
+//! `a`, `b`, and `c`.  Depending on the opcode, `a` and `b` are either
+//! register numbers or immediate values, and `c` is always the register
+//! the result is written to.  Which opcodes a program can use, and how
+//! they are numbered, is described by an `InstructionSet`.
+//!
+//! A program may bind the instruction pointer to a register.  The register
+//! then holds the instruction pointer while each instruction runs, so
+//! writing to it jumps.  The program halts when the instruction pointer
+//! leaves the program.
+
+pub use crate::asm::{assemble, disassemble, encode};
+pub use crate::compile::Compiled;
+pub use crate::decompile::{decompile, Assign, Block, Decompiled, Exit, Expr, Loop};
+pub use crate::debug::{Breakpoint, Debugger, Stop};
+pub use crate::error::{Error, Result};
+pub use crate::machine::{Instruction, Machine, Overflow, Program, Word};
+pub use crate::mapping::{find_mapping, find_mappings, Mapping, MappingError, Sample};
+pub use crate::opcode::{InstructionSet, Mode, Opcode, Operation};
+pub use crate::trace::{HotLoop, Profile, TraceEvent, TraceFormat, TraceWriter};
+
+mod asm;
+mod compile;
+mod debug;
+mod decompile;
+mod error;
+mod machine;
+mod mapping;
+mod opcode;
+mod trace;
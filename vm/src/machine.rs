@@ -0,0 +1,359 @@
+//! The machine itself, and running programs on it.
+
+use serde::Serialize;
+use std::fmt;
+use crate::{
+    Error,
+    Opcode,
+    Result,
+    opcode::Operation,
+};
+
+/// The values held in registers, and used as arguments.  Machines with
+/// narrower words keep their values masked to that width.
+pub type Word = u64;
+
+/// A single instruction, with its opcode already decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Instruction {
+    pub op: Opcode,
+    pub a: Word,
+    pub b: Word,
+    pub c: Word,
+}
+
+impl Instruction {
+    pub fn new(op: Opcode, a: Word, b: Word, c: Word) -> Instruction {
+        Instruction { op, a, b, c }
+    }
+
+    /// Decode a numeric instruction, `opmap` giving the opcode for each
+    /// number.
+    pub fn decode(raw: [Word; 4], opmap: &[Opcode]) -> Result<Instruction> {
+        let op = opmap.get(raw[0] as usize).ok_or(Error::BadOpcode(raw[0]))?;
+        Ok(Instruction::new(*op, raw[1], raw[2], raw[3]))
+    }
+}
+
+/// A program, and the register the instruction pointer is bound to, if
+/// any.  Without a binding, the program just runs straight through.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Program {
+    pub ip_register: Option<Word>,
+    pub code: Vec<Instruction>,
+}
+
+impl Program {
+    /// Enough registers for everything the program refers to.
+    pub fn register_count(&self) -> usize {
+        let mut count = self.ip_register.map(|r| r as usize + 1).unwrap_or(0);
+        for instr in &self.code {
+            if instr.op.a_is_register() {
+                count = count.max(instr.a as usize + 1);
+            }
+            if instr.op.b_is_register() {
+                count = count.max(instr.b as usize + 1);
+            }
+            count = count.max(instr.c as usize + 1);
+        }
+        count
+    }
+
+    pub fn new(ip_register: Option<Word>, code: Vec<Instruction>) -> Program {
+        Program { ip_register, code }
+    }
+
+    /// Decode a numeric program, `opmap` giving the opcode for each
+    /// number.
+    pub fn decode(ip_register: Option<Word>, raw: &[[Word; 4]], opmap: &[Opcode]) -> Result<Program> {
+        let code = raw.iter()
+            .map(|&instr| Instruction::decode(instr, opmap))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Program::new(ip_register, code))
+    }
+}
+
+/// What to do when the result of an addition or multiplication doesn't fit
+/// in a word.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Overflow {
+    /// Keep the low bits of the result.  This is the default.
+    #[default]
+    Wrapping,
+
+    /// Stop with an error.
+    Checked,
+
+    /// Use the largest value a word can hold.
+    Saturating,
+
+    /// Treat the machine as having 64-bit words, whatever its width, and
+    /// stop with an error if a result doesn't fit in that.
+    Widened,
+}
+
+impl Overflow {
+    pub fn name(self) -> &'static str {
+        match self {
+            Overflow::Wrapping => "wrapping",
+            Overflow::Checked => "checked",
+            Overflow::Saturating => "saturating",
+            Overflow::Widened => "widened",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Overflow> {
+        [Overflow::Wrapping, Overflow::Checked, Overflow::Saturating, Overflow::Widened]
+            .iter().cloned().find(|p| p.name() == name)
+    }
+
+    /// Add two words, following this policy.  `mask` has a bit set for
+    /// each bit of the word.  Returns None if the result is an error.
+    #[inline]
+    pub(crate) fn add(self, a: Word, b: Word, mask: Word) -> Option<Word> {
+        match self {
+            Overflow::Wrapping => Some(a.wrapping_add(b) & mask),
+            _ => self.limit(a.checked_add(b), mask),
+        }
+    }
+
+    /// Multiply two words, following this policy.
+    #[inline]
+    pub(crate) fn mul(self, a: Word, b: Word, mask: Word) -> Option<Word> {
+        match self {
+            Overflow::Wrapping => Some(a.wrapping_mul(b) & mask),
+            _ => self.limit(a.checked_mul(b), mask),
+        }
+    }
+
+    /// Deal with a result that is None if it didn't even fit in 64 bits.
+    fn limit(self, result: Option<Word>, mask: Word) -> Option<Word> {
+        match (self, result) {
+            (Overflow::Saturating, _) => Some(result.unwrap_or(mask).min(mask)),
+            (_, Some(value)) if value <= mask => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Shift left, giving zero for shifts of a whole word or more.  The
+/// result still needs masking to the machine's width.
+#[inline]
+pub(crate) fn shl(a: Word, b: Word) -> Word {
+    if b < 64 { a << b } else { 0 }
+}
+
+/// Shift right, giving zero for shifts of a whole word or more.
+#[inline]
+pub(crate) fn shr(a: Word, b: Word) -> Word {
+    if b < 64 { a >> b } else { 0 }
+}
+
+/// The state of a machine: its registers, and where it is in the program.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Machine {
+    pub(crate) regs: Vec<Word>,
+
+    /// The width of a word, in bits.
+    width: u32,
+
+    /// The index of the next instruction to run.
+    pub(crate) ip: usize,
+
+    /// How many instructions have been run.
+    pub(crate) steps: u64,
+
+    /// If set, running more than this many instructions is an error.
+    pub(crate) limit: Option<u64>,
+
+    /// What happens when arithmetic overflows.
+    pub(crate) overflow: Overflow,
+}
+
+impl Machine {
+    /// A machine with the given number of registers, all zero, and words of
+    /// the given number of bits, from 1 to 64.
+    pub fn new(registers: usize, width: u32) -> Machine {
+        Machine::with_registers(&vec![0; registers], width)
+    }
+
+    /// A machine with the given initial register values.
+    pub fn with_registers(values: &[Word], width: u32) -> Machine {
+        assert!((1 ..= 64).contains(&width), "Word width must be from 1 to 64 bits");
+        let mut m = Machine {
+            regs: values.to_vec(),
+            width,
+            ip: 0,
+            steps: 0,
+            limit: None,
+            overflow: Overflow::Wrapping,
+        };
+        let mask = m.mask();
+        for r in &mut m.regs {
+            *r &= mask;
+        }
+        m
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// The bits a register can hold.
+    pub(crate) fn mask(&self) -> Word {
+        if self.overflow == Overflow::Widened {
+            Word::MAX
+        } else {
+            Word::MAX >> (64 - self.width)
+        }
+    }
+
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// Change what happens when arithmetic overflows.  Changing from
+    /// widened to something else keeps just the bits that fit in the
+    /// machine's width.
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.overflow = overflow;
+        let mask = self.mask();
+        for r in &mut self.regs {
+            *r &= mask;
+        }
+    }
+
+    pub fn registers(&self) -> &[Word] {
+        &self.regs
+    }
+
+    pub fn register(&self, reg: Word) -> Result<Word> {
+        match self.regs.get(reg as usize) {
+            Some(&value) => Ok(value),
+            None => Err(self.bad_register(reg)),
+        }
+    }
+
+    pub fn set_register(&mut self, reg: Word, value: Word) -> Result<()> {
+        let mask = self.mask();
+        match self.regs.get_mut(reg as usize) {
+            Some(r) => {
+                *r = value & mask;
+                Ok(())
+            }
+            None => Err(self.bad_register(reg)),
+        }
+    }
+
+    fn bad_register(&self, reg: Word) -> Error {
+        Error::BadRegister {
+            register: reg,
+            count: self.regs.len(),
+        }
+    }
+
+    /// The index of the next instruction to run.
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn set_ip(&mut self, ip: usize) {
+        self.ip = ip;
+    }
+
+    /// The number of instructions that have been run.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Limit how many instructions can be run, so that a program that
+    /// never halts gives an error instead of running forever.
+    pub fn set_step_limit(&mut self, limit: Option<u64>) {
+        self.limit = limit;
+    }
+
+    pub fn step_limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// Perform a single instruction, without regard to where it is in the
+    /// program.
+    pub fn exec(&mut self, instr: &Instruction) -> Result<()> {
+        let a = self.operand(instr.a, instr.op.a_is_register())?;
+        // Set ignores `b`, so this doesn't complain if it isn't a register.
+        let b = self.operand(instr.b, instr.op.b_is_register())?;
+
+        let mask = self.mask();
+        let overflow = |result: Option<Word>| {
+            result.ok_or(Error::Overflow { op: instr.op, a, b, width: mask.count_ones() })
+        };
+        let c = match instr.op.operation() {
+            Operation::Add => overflow(self.overflow.add(a, b, mask))?,
+            Operation::Mul => overflow(self.overflow.mul(a, b, mask))?,
+            Operation::Ban => a & b,
+            Operation::Bor => a | b,
+            Operation::Set => a,
+            Operation::Gt => if a > b { 1 } else { 0 },
+            Operation::Eq => if a == b { 1 } else { 0 },
+            Operation::Div => a.checked_div(b).ok_or(Error::DivideByZero { op: instr.op, a })?,
+            Operation::Mod => a.checked_rem(b).ok_or(Error::DivideByZero { op: instr.op, a })?,
+            Operation::Shl => shl(a, b),
+            Operation::Shr => shr(a, b),
+        };
+
+        self.set_register(instr.c, c)
+    }
+
+    /// The value of an argument, which is either a register, or the value
+    /// itself.
+    fn operand(&self, arg: Word, is_register: bool) -> Result<Word> {
+        if is_register {
+            self.register(arg)
+        } else {
+            Ok(arg)
+        }
+    }
+
+    /// Run the next instruction of the program.  Returns false, without
+    /// doing anything, if the instruction pointer is outside of the
+    /// program, which means it has halted.
+    pub fn step(&mut self, program: &Program) -> Result<bool> {
+        let instr = match program.code.get(self.ip) {
+            Some(instr) => instr,
+            None => return Ok(false),
+        };
+        if self.limit == Some(self.steps) {
+            return Err(Error::StepLimit(self.steps));
+        }
+
+        match program.ip_register {
+            None => {
+                self.exec(instr)?;
+                self.ip += 1;
+            }
+            Some(reg) => {
+                self.set_register(reg, self.ip as Word)?;
+                self.exec(instr)?;
+                // A value too big for the instruction pointer is well
+                // outside of the program, so halt there.
+                self.ip = (self.register(reg)? as usize).saturating_add(1);
+            }
+        }
+        self.steps += 1;
+        Ok(true)
+    }
+
+    /// Run the program until it halts.
+    pub fn run(&mut self, program: &Program) -> Result<()> {
+        while self.step(program)? {
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,358 @@
+//! Working out which number goes with each opcode, from samples of the
+//! device's behavior.
+//!
+//! Each sample shows the registers before and after a single numeric
+//! instruction.  An opcode can only have a given number if it behaves the
+//! same way as every sample using that number.  These constraints usually
+//! narrow things down to a single mapping, but the samples might not be
+//! enough to tell some opcodes apart, or might contradict each other.
+
+use std::{
+    collections::BTreeSet,
+    error,
+    fmt,
+};
+use crate::{
+    Instruction,
+    InstructionSet,
+    Machine,
+    Opcode,
+    Word,
+};
+
+/// A single sample: the registers before and after an instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Sample {
+    pub before: Vec<Word>,
+    pub instr: [Word; 4],
+    pub after: Vec<Word>,
+
+    /// Where the sample came from, for messages.
+    pub line: Option<usize>,
+}
+
+impl Sample {
+    /// The number of the opcode used by this sample.
+    pub fn code(&self) -> Word {
+        self.instr[0]
+    }
+
+    /// Does this sample behave like the given opcode, on a machine with
+    /// the given word width?  An opcode that refers to a register the
+    /// machine doesn't have can't match.
+    pub fn matches(&self, op: Opcode, width: u32) -> bool {
+        let mut machine = Machine::with_registers(&self.before, width);
+        let instr = Instruction::new(op, self.instr[1], self.instr[2], self.instr[3]);
+        machine.exec(&instr).is_ok() && machine.registers() == &self.after[..]
+    }
+}
+
+/// The mappings consistent with a set of samples.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mapping {
+    /// The consistent mappings, each giving the opcode for each number.
+    pub solutions: Vec<Vec<Opcode>>,
+
+    /// False if the search stopped at the limit, so there may be more
+    /// solutions.
+    pub complete: bool,
+
+    /// The numbers that could be more than one opcode, with the opcodes
+    /// they could be.  This is exact, even if the search stopped early.
+    pub ambiguous: Vec<(Word, Vec<Opcode>)>,
+}
+
+impl Mapping {
+    /// The mapping, if there is only one.
+    pub fn unique(&self) -> Option<&[Opcode]> {
+        if self.ambiguous.is_empty() && self.solutions.len() == 1 {
+            Some(&self.solutions[0])
+        } else {
+            None
+        }
+    }
+}
+
+/// Why the samples can't be satisfied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MappingError {
+    /// There is no consistent mapping.  `samples` are the indices of the
+    /// samples that lead to the contradiction.
+    Contradiction {
+        message: String,
+        samples: Vec<usize>,
+        lines: Vec<Option<usize>>,
+    },
+
+    /// There is more than one consistent mapping.
+    Ambiguous(Vec<(Word, Vec<Opcode>)>),
+}
+
+/// Which opcodes are still possible for each number, and, for those that
+/// aren't, the first sample that ruled them out.  Opcodes are referred to
+/// by their position in `ops`.
+struct Constraints {
+    ops: Vec<Opcode>,
+    possible: Vec<Vec<bool>>,
+    ruled_out_by: Vec<Vec<Option<usize>>>,
+}
+
+/// Find every mapping from numbers to the opcodes in the set consistent
+/// with the samples, stopping after `limit` of them.  Returns an error
+/// describing the contradiction if there are none.
+pub fn find_mappings(samples: &[Sample], set: &InstructionSet, width: u32, limit: usize)
+    -> Result<Mapping, MappingError>
+{
+    let count = set.len();
+    let mut cons = Constraints {
+        ops: set.ops().to_vec(),
+        possible: vec![vec![true; count]; count],
+        ruled_out_by: vec![vec![None; count]; count],
+    };
+
+    for (index, sample) in samples.iter().enumerate() {
+        let code = sample.code() as usize;
+        if code >= count {
+            return Err(contradiction(samples, vec![index], format!(
+                "opcode number {} is out of range, there are only {} opcodes",
+                sample.code(), count)));
+        }
+
+        let mut any = false;
+        for (op, &opcode) in set.ops().iter().enumerate() {
+            if sample.matches(opcode, width) {
+                any = true;
+            } else if cons.possible[code][op] {
+                cons.possible[code][op] = false;
+                cons.ruled_out_by[code][op] = Some(index);
+            }
+        }
+        if !any {
+            return Err(contradiction(samples, vec![index], "sample matches no opcode".to_string()));
+        }
+    }
+
+    if let Some(err) = hall_violation(samples, &cons) {
+        return Err(err);
+    }
+
+    // Search for all of the mappings, trying the most constrained numbers
+    // first.
+    let mut order: Vec<usize> = (0 .. count).collect();
+    order.sort_by_key(|&code| cons.possible[code].iter().filter(|&&p| p).count());
+    let mut search = Search {
+        cons: &cons,
+        order,
+        assigned: vec![None; count],
+        used: vec![false; count],
+        solutions: vec![],
+        limit,
+    };
+    let complete = search.run(0);
+    let solutions = search.solutions;
+
+    let ambiguous = (0 .. count).filter_map(|code| {
+        let ops: Vec<Opcode> = (0 .. count)
+            .filter(|&op| cons.possible[code][op] && feasible(&cons, code, op))
+            .map(|op| cons.ops[op])
+            .collect();
+        if ops.len() > 1 {
+            Some((code as Word, ops))
+        } else {
+            None
+        }
+    }).collect();
+
+    Ok(Mapping {
+        solutions,
+        complete,
+        ambiguous,
+    })
+}
+
+/// Find the single mapping to the opcodes in the set consistent with the
+/// samples.
+pub fn find_mapping(samples: &[Sample], set: &InstructionSet, width: u32) -> Result<Vec<Opcode>, MappingError> {
+    // Two solutions are enough to show there is more than one.
+    let mapping = find_mappings(samples, set, width, 2)?;
+    match mapping.unique() {
+        Some(opmap) => Ok(opmap.to_vec()),
+        None => Err(MappingError::Ambiguous(mapping.ambiguous)),
+    }
+}
+
+fn contradiction(samples: &[Sample], mut indices: Vec<usize>, message: String) -> MappingError {
+    indices.sort();
+    indices.dedup();
+    MappingError::Contradiction {
+        message,
+        lines: indices.iter().map(|&i| samples[i].line).collect(),
+        samples: indices,
+    }
+}
+
+/// Check that each number can be given a different opcode.  If not, find
+/// a set of numbers with too few opcodes between them, and blame the
+/// samples that ruled out the other opcodes.
+fn hall_violation(samples: &[Sample], cons: &Constraints) -> Option<MappingError> {
+    let count = cons.ops.len();
+
+    // Find a maximum matching using augmenting paths.
+    let mut op_owner: Vec<Option<usize>> = vec![None; count];
+    for code in 0 .. count {
+        let mut seen = vec![false; count];
+        if augment(cons, code, &mut seen, &mut op_owner) {
+            continue;
+        }
+
+        // The numbers reachable from this one by alternating paths only
+        // have the opcodes reachable between them, and there is one fewer
+        // of those.
+        let mut codes = BTreeSet::new();
+        let mut ops = BTreeSet::new();
+        let mut work = vec![code];
+        while let Some(c) = work.pop() {
+            if !codes.insert(c) {
+                continue;
+            }
+            for (op, owner) in op_owner.iter().enumerate() {
+                if cons.possible[c][op] && ops.insert(op) {
+                    if let Some(owner) = *owner {
+                        work.push(owner);
+                    }
+                }
+            }
+        }
+
+        let mut blame = vec![];
+        for &c in &codes {
+            for op in 0 .. count {
+                if !ops.contains(&op) {
+                    blame.extend(cons.ruled_out_by[c][op]);
+                }
+            }
+        }
+
+        let names: Vec<_> = ops.iter().map(|&op| cons.ops[op].name()).collect();
+        let nums: Vec<_> = codes.iter().map(|c| c.to_string()).collect();
+        let message = if names.is_empty() {
+            format!("opcode number {} matches no opcode", nums.join(", "))
+        } else {
+            format!("opcode numbers {} can only be {}, which is too few opcodes",
+                    nums.join(", "), names.join(", "))
+        };
+        return Some(contradiction(samples, blame, message));
+    }
+    None
+}
+
+/// Try to find an opcode for `code`, moving other numbers to different
+/// opcodes if necessary.
+fn augment(cons: &Constraints, code: usize, seen: &mut [bool], op_owner: &mut [Option<usize>]) -> bool {
+    for op in 0 .. cons.ops.len() {
+        if !cons.possible[code][op] || seen[op] {
+            continue;
+        }
+        seen[op] = true;
+        let free = match op_owner[op] {
+            None => true,
+            Some(other) => augment(cons, other, seen, op_owner),
+        };
+        if free {
+            op_owner[op] = Some(code);
+            return true;
+        }
+    }
+    false
+}
+
+/// Is there a complete mapping that gives `code` the opcode `op`?  This
+/// doesn't need the search, so it works even when there are too many
+/// mappings to list.
+fn feasible(cons: &Constraints, code: usize, op: usize) -> bool {
+    let count = cons.ops.len();
+    let mut op_owner = vec![None; count];
+    op_owner[op] = Some(code);
+    (0 .. count).filter(|&other| other != code).all(|other| {
+        // Marking `op` as seen keeps it from being taken away.
+        let mut seen = vec![false; count];
+        seen[op] = true;
+        augment(cons, other, &mut seen, &mut op_owner)
+    })
+}
+
+/// The state of the backtracking search for mappings.
+struct Search<'a> {
+    cons: &'a Constraints,
+
+    /// The order to assign the numbers in.
+    order: Vec<usize>,
+
+    assigned: Vec<Option<Opcode>>,
+    used: Vec<bool>,
+    solutions: Vec<Vec<Opcode>>,
+    limit: usize,
+}
+
+impl<'a> Search<'a> {
+    /// Assign the numbers from `order[depth]` on.  Returns false if the
+    /// search stopped at the limit.
+    fn run(&mut self, depth: usize) -> bool {
+        if self.solutions.len() >= self.limit {
+            return false;
+        }
+        if depth == self.order.len() {
+            self.solutions.push(self.assigned.iter().map(|op| op.unwrap()).collect());
+            return true;
+        }
+
+        let code = self.order[depth];
+        for (op, &opcode) in self.cons.ops.iter().enumerate() {
+            if !self.cons.possible[code][op] || self.used[op] {
+                continue;
+            }
+            self.assigned[code] = Some(opcode);
+            self.used[op] = true;
+            let finished = self.run(depth + 1);
+            self.used[op] = false;
+            self.assigned[code] = None;
+            if !finished {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl fmt::Display for MappingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MappingError::Contradiction { message, samples, lines } => {
+                write!(f, "Samples are contradictory: {}", message)?;
+                let names: Vec<_> = samples.iter().zip(lines).map(|(index, line)| match line {
+                    Some(line) => format!("sample {} (line {})", index + 1, line),
+                    None => format!("sample {}", index + 1),
+                }).collect();
+                if !names.is_empty() {
+                    write!(f, ", because of {}", names.join(", "))?;
+                }
+                Ok(())
+            }
+            MappingError::Ambiguous(codes) => {
+                let parts: Vec<_> = codes.iter().map(|(code, ops)| {
+                    let names: Vec<_> = ops.iter().map(|op| op.name()).collect();
+                    format!("{} could be {}", code, names.join(" or "))
+                }).collect();
+                write!(f, "Samples don't determine every opcode: {}", parts.join("; "))
+            }
+        }
+    }
+}
+
+impl error::Error for MappingError {}
+
+impl From<MappingError> for common::Error {
+    fn from(err: MappingError) -> common::Error {
+        common::Error::Other(Box::new(err))
+    }
+}
@@ -0,0 +1,243 @@
+//! The opcodes, and how each one behaves.
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use serde::{Serialize, Serializer};
+use crate::Error;
+
+/// The opcodes.  The first sixteen are the ones given in the problem
+/// description, in the order given there, and the rest are extensions.  The
+/// numbers used for them in a program have to be determined separately.
+///
+/// To add an opcode, add it here, and add a row for it to `OPCODES`, in
+/// the same position.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, FromPrimitive)]
+pub enum Opcode {
+    Addr,
+    Addi,
+    Mulr,
+    Muli,
+    Banr,
+    Bani,
+    Borr,
+    Bori,
+    Setr,
+    Seti,
+    Gtir,
+    Gtri,
+    Gtrr,
+    Eqir,
+    Eqri,
+    Eqrr,
+
+    // Extensions, which the device doesn't have.
+    Divr,
+    Divi,
+    Modr,
+    Modi,
+    Shlr,
+    Shli,
+    Shrr,
+    Shri,
+}
+
+/// Operation modes.  The set instructions ignore the second argument, but
+/// it will always be ok to just use one of the modes (we'll use Reg just
+/// to avoid needing an ImmImm mode that only ignores the second arg).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    RegReg,
+    ImmReg,
+    RegImm
+}
+
+/// Operations themselves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Add,
+    Mul,
+    Ban,
+    Bor,
+    Set,
+    Gt,
+    Eq,
+
+    /// Unsigned division and remainder.  Dividing by zero is an error.
+    Div,
+    Mod,
+
+    /// Shifts.  Bits shifted out are lost, whatever the overflow policy,
+    /// and shifting by the word size or more gives zero.
+    Shl,
+    Shr,
+}
+
+/// How an opcode behaves.
+struct Info {
+    /// The mnemonic used in assembly.
+    name: &'static str,
+    mode: Mode,
+    operation: Operation,
+}
+
+/// The description of each opcode, in the same order as `Opcode`.
+static OPCODES: [Info; Opcode::COUNT] = [
+    Info { name: "addr", mode: Mode::RegReg, operation: Operation::Add },
+    Info { name: "addi", mode: Mode::RegImm, operation: Operation::Add },
+    Info { name: "mulr", mode: Mode::RegReg, operation: Operation::Mul },
+    Info { name: "muli", mode: Mode::RegImm, operation: Operation::Mul },
+    Info { name: "banr", mode: Mode::RegReg, operation: Operation::Ban },
+    Info { name: "bani", mode: Mode::RegImm, operation: Operation::Ban },
+    Info { name: "borr", mode: Mode::RegReg, operation: Operation::Bor },
+    Info { name: "bori", mode: Mode::RegImm, operation: Operation::Bor },
+    Info { name: "setr", mode: Mode::RegReg, operation: Operation::Set },
+    Info { name: "seti", mode: Mode::ImmReg, operation: Operation::Set },
+    Info { name: "gtir", mode: Mode::ImmReg, operation: Operation::Gt },
+    Info { name: "gtri", mode: Mode::RegImm, operation: Operation::Gt },
+    Info { name: "gtrr", mode: Mode::RegReg, operation: Operation::Gt },
+    Info { name: "eqir", mode: Mode::ImmReg, operation: Operation::Eq },
+    Info { name: "eqri", mode: Mode::RegImm, operation: Operation::Eq },
+    Info { name: "eqrr", mode: Mode::RegReg, operation: Operation::Eq },
+    Info { name: "divr", mode: Mode::RegReg, operation: Operation::Div },
+    Info { name: "divi", mode: Mode::RegImm, operation: Operation::Div },
+    Info { name: "modr", mode: Mode::RegReg, operation: Operation::Mod },
+    Info { name: "modi", mode: Mode::RegImm, operation: Operation::Mod },
+    Info { name: "shlr", mode: Mode::RegReg, operation: Operation::Shl },
+    Info { name: "shli", mode: Mode::RegImm, operation: Operation::Shl },
+    Info { name: "shrr", mode: Mode::RegReg, operation: Operation::Shr },
+    Info { name: "shri", mode: Mode::RegImm, operation: Operation::Shr },
+];
+
+impl Opcode {
+    /// The number of opcodes.
+    pub const COUNT: usize = 24;
+
+    /// The number of opcodes the device has, which come first.
+    pub const DEVICE_COUNT: usize = 16;
+
+    fn info(self) -> &'static Info {
+        &OPCODES[self as usize]
+    }
+
+    /// The mnemonic used for this opcode in assembly, such as "addi".
+    pub fn name(self) -> &'static str {
+        self.info().name
+    }
+
+    /// Look up an opcode by its mnemonic.
+    pub fn from_name(name: &str) -> Option<Opcode> {
+        OPCODES.iter().position(|info| info.name == name).and_then(Opcode::from_index)
+    }
+
+    /// Is the `a` argument a register?
+    pub fn a_is_register(self) -> bool {
+        self.mode() != Mode::ImmReg
+    }
+
+    /// Is the `b` argument a register?  This is false for the set
+    /// opcodes, which ignore `b`.
+    pub fn b_is_register(self) -> bool {
+        self.mode() != Mode::RegImm && self.operation() != Operation::Set
+    }
+
+    /// How the `a` and `b` arguments of this opcode are interpreted.
+    pub fn mode(self) -> Mode {
+        self.info().mode
+    }
+
+    /// What this opcode computes.
+    pub fn operation(self) -> Operation {
+        self.info().operation
+    }
+
+    /// The opcode at the given position in the list above.
+    pub fn from_index(index: usize) -> Option<Opcode> {
+        FromPrimitive::from_usize(index)
+    }
+
+    /// Return an iterator over all of the opcodes, including the
+    /// extensions.
+    pub fn iter() -> OpcodeIter {
+        OpcodeIter(0)
+    }
+}
+
+/// A set of opcodes that a machine understands, such as the device's
+/// sixteen.  Programs number the opcodes from 0 to one less than the size
+/// of the set, in some order that has to be worked out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstructionSet {
+    ops: Vec<Opcode>,
+}
+
+impl InstructionSet {
+    /// A set of the given opcodes.  It is an error to give an opcode more
+    /// than once.
+    pub fn new(ops: Vec<Opcode>) -> crate::Result<InstructionSet> {
+        for (i, &op) in ops.iter().enumerate() {
+            if ops[.. i].contains(&op) {
+                return Err(Error::DuplicateOpcode(op));
+            }
+        }
+        Ok(InstructionSet { ops })
+    }
+
+    /// The device's own sixteen opcodes.
+    pub fn device() -> InstructionSet {
+        InstructionSet { ops: Opcode::iter().take(Opcode::DEVICE_COUNT).collect() }
+    }
+
+    /// Every opcode, including the extensions.
+    pub fn extended() -> InstructionSet {
+        InstructionSet { ops: Opcode::iter().collect() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// The opcode at the given position in the set.
+    pub fn get(&self, index: usize) -> Option<Opcode> {
+        self.ops.get(index).cloned()
+    }
+
+    pub fn contains(&self, op: Opcode) -> bool {
+        self.ops.contains(&op)
+    }
+
+    pub fn ops(&self) -> &[Opcode] {
+        &self.ops
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Opcode> + '_ {
+        self.ops.iter().cloned()
+    }
+}
+
+/// Opcodes are written out by name.
+impl Serialize for Opcode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+pub struct OpcodeIter(usize);
+
+impl Iterator for OpcodeIter {
+    type Item = Opcode;
+
+    fn next(&mut self) -> Option<Opcode> {
+        let cur = self.0;
+
+        if cur < Opcode::COUNT {
+            self.0 += 1;
+            Opcode::from_index(cur)
+        } else {
+            None
+        }
+    }
+}
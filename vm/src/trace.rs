@@ -0,0 +1,218 @@
+//! Recording what a program does as it runs.
+//!
+//! Each instruction run can be written out as a `TraceEvent`, either as a
+//! line of JSON, or a row of CSV, and the events can be gathered into a
+//! `Profile` showing where the program spends its time.
+
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+use crate::{
+    Instruction,
+    Machine,
+    Opcode,
+    Overflow,
+    Program,
+    Result,
+    Word,
+};
+
+/// A single instruction that was run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct TraceEvent {
+    /// The number of this step, counting from 1.
+    pub step: u64,
+    pub ip: usize,
+    pub instr: Instruction,
+    pub before: Vec<Word>,
+    pub after: Vec<Word>,
+
+    /// Where the program goes next.
+    pub next_ip: usize,
+
+    /// The machine's overflow policy, which affects the results of
+    /// arithmetic.
+    pub overflow: Overflow,
+}
+
+impl Machine {
+    /// Run the next instruction of the program, like `step`, and describe
+    /// what it did.  Returns None if the program has halted.
+    pub fn step_traced(&mut self, program: &Program) -> Result<Option<TraceEvent>> {
+        let ip = self.ip();
+        let instr = match program.code.get(ip) {
+            Some(&instr) => instr,
+            None => return Ok(None),
+        };
+        let before = self.registers().to_vec();
+        self.step(program)?;
+        Ok(Some(TraceEvent {
+            step: self.steps(),
+            ip,
+            instr,
+            before,
+            after: self.registers().to_vec(),
+            next_ip: self.ip(),
+            overflow: self.overflow(),
+        }))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// One JSON object per line.
+    Jsonl,
+
+    /// A header, and then one row per instruction, with a column for each
+    /// register before and after.
+    Csv,
+}
+
+/// Writes trace events in the given format.
+pub struct TraceWriter<W: Write> {
+    out: W,
+    format: TraceFormat,
+    started: bool,
+}
+
+impl<W: Write> TraceWriter<W> {
+    pub fn new(out: W, format: TraceFormat) -> TraceWriter<W> {
+        TraceWriter {
+            out,
+            format,
+            started: false,
+        }
+    }
+
+    pub fn write(&mut self, event: &TraceEvent) -> io::Result<()> {
+        match self.format {
+            TraceFormat::Jsonl => {
+                serde_json::to_writer(&mut self.out, event)?;
+                writeln!(self.out)
+            }
+            TraceFormat::Csv => {
+                if !self.started {
+                    let regs = |when: &str| -> Vec<String> {
+                        (0 .. event.before.len()).map(|i| format!("r{}_{}", i, when)).collect()
+                    };
+                    writeln!(self.out, "step,ip,op,a,b,c,{},{},next_ip,overflow",
+                             regs("before").join(","), regs("after").join(","))?;
+                }
+                let join = |regs: &[Word]| -> String {
+                    regs.iter().map(|r| r.to_string()).collect::<Vec<_>>().join(",")
+                };
+                writeln!(self.out, "{},{},{},{},{},{},{},{},{},{}",
+                         event.step, event.ip, event.instr.op.name(),
+                         event.instr.a, event.instr.b, event.instr.c,
+                         join(&event.before), join(&event.after), event.next_ip,
+                         event.overflow)
+            }
+        }?;
+        self.started = true;
+        Ok(())
+    }
+
+    /// Finish writing, returning the output.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+/// Where a program spent its time.
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    /// How many times the instruction at each address ran.
+    pub hits: Vec<u64>,
+
+    /// How many times each opcode ran.
+    pub opcodes: HashMap<Opcode, u64>,
+
+    /// How many times each backward jump was taken, keyed by the address
+    /// jumped from, and the address jumped to.
+    back_edges: HashMap<(usize, usize), u64>,
+}
+
+/// A loop found by a backward jump.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HotLoop {
+    /// The first and last addresses of the loop.
+    pub start: usize,
+    pub end: usize,
+
+    /// How many times the backward jump was taken.
+    pub iterations: u64,
+
+    /// How many instructions in the loop's range ran, in total.
+    pub instructions: u64,
+}
+
+impl Profile {
+    pub fn new() -> Profile {
+        Profile::default()
+    }
+
+    pub fn record(&mut self, event: &TraceEvent) {
+        if self.hits.len() <= event.ip {
+            self.hits.resize(event.ip + 1, 0);
+        }
+        self.hits[event.ip] += 1;
+        *self.opcodes.entry(event.instr.op).or_insert(0) += 1;
+        if event.next_ip <= event.ip {
+            *self.back_edges.entry((event.ip, event.next_ip)).or_insert(0) += 1;
+        }
+    }
+
+    /// The total number of instructions run.
+    pub fn total(&self) -> u64 {
+        self.hits.iter().sum()
+    }
+
+    /// The loops found, busiest first.
+    pub fn hot_loops(&self) -> Vec<HotLoop> {
+        let mut loops: Vec<_> = self.back_edges.iter().map(|(&(end, start), &iterations)| {
+            HotLoop {
+                start,
+                end,
+                iterations,
+                instructions: self.hits[start ..= end].iter().sum(),
+            }
+        }).collect();
+        loops.sort_by_key(|l| (std::cmp::Reverse(l.instructions), l.start, l.end));
+        loops
+    }
+
+    /// A readable summary of the profile, showing the program with the
+    /// counts alongside.
+    pub fn report(&self, program: &Program) -> Vec<String> {
+        let mut lines = vec![];
+        let total = self.total().max(1);
+
+        lines.push(format!("{} instructions run", self.total()));
+        lines.push("".to_string());
+        lines.push("Instructions:".to_string());
+        for (ip, instr) in program.code.iter().enumerate() {
+            let hits = self.hits.get(ip).cloned().unwrap_or(0);
+            lines.push(format!("{:>12} {:5.1}% {:4}: {}", hits,
+                               hits as f64 * 100.0 / total as f64, ip, instr));
+        }
+
+        lines.push("".to_string());
+        lines.push("Opcodes:".to_string());
+        let mut opcodes: Vec<_> = self.opcodes.iter().collect();
+        opcodes.sort_by_key(|&(op, &count)| (std::cmp::Reverse(count), op.name()));
+        for (op, count) in opcodes {
+            lines.push(format!("{:>12} {}", count, op.name()));
+        }
+
+        lines.push("".to_string());
+        lines.push("Hot loops:".to_string());
+        for l in self.hot_loops() {
+            lines.push(format!("{:>12} {:4}..={:<4} {} iterations",
+                               l.instructions, l.start, l.end, l.iterations));
+        }
+        lines
+    }
+}
@@ -0,0 +1,66 @@
+use vm::{assemble, disassemble, encode, Instruction, InstructionSet, Opcode, Program};
+use crate::util::lines;
+
+mod util;
+
+const SOURCE: &str = "\
+#ip 3
+addi r1 5 r0
+seti 7 0 r2
+gtir 9 r2 r1
+eqrr r0 r1 r3
+";
+
+#[test]
+fn text_round_trip() {
+    let program = assemble(&lines(SOURCE)).unwrap();
+    assert_eq!(program.ip_register, Some(3));
+    assert_eq!(program.code[0], Instruction::new(Opcode::Addi, 1, 5, 0));
+    assert_eq!(program.code[2], Instruction::new(Opcode::Gtir, 9, 2, 1));
+
+    let text = disassemble(&program);
+    assert_eq!(text[1], "addi r1 5 r0        ; r0 = r1 + 5");
+    assert_eq!(text[3], "gtir 9 r2 r1        ; r1 = 9 > r2");
+
+    let again = assemble(&lines(&text.join("\n"))).unwrap();
+    assert_eq!(again, program);
+}
+
+#[test]
+fn numeric_round_trip() {
+    let program = assemble(&lines(SOURCE)).unwrap();
+
+    // Number the opcodes in reverse, so the map isn't the identity.
+    let opmap: Vec<Opcode> = InstructionSet::device().iter().collect::<Vec<_>>().into_iter().rev().collect();
+    let raw = encode(&program, &opmap).unwrap();
+    assert_eq!(raw[0], [14, 1, 5, 0]);
+
+    let decoded = Program::decode(program.ip_register, &raw, &opmap).unwrap();
+    assert_eq!(decoded, program);
+}
+
+#[test]
+fn extensions() {
+    let program = assemble(&lines("divr r0 r1 r2\nmodi r2 7 r3\nshli r3 4 r0\nshrr r0 r1 r1")).unwrap();
+    assert_eq!(program.code[1], Instruction::new(Opcode::Modi, 2, 7, 3));
+
+    let text = disassemble(&program);
+    assert_eq!(text[0], "divr r0 r1 r2       ; r2 = r0 / r1");
+    assert_eq!(text[2], "shli r3 4 r0        ; r0 = r3 << 4");
+
+    // The device's numbering has no room for them.
+    let opmap: Vec<Opcode> = InstructionSet::device().iter().collect();
+    assert!(encode(&program, &opmap).is_err());
+}
+
+#[test]
+fn operand_errors() {
+    let err = assemble(&lines("addi r1 r5 r0")).unwrap_err();
+    assert_eq!((err.line, err.column), (1, 9));
+
+    let err = assemble(&lines("addi r1 5 r0\nmuli 1 5 r0")).unwrap_err();
+    assert_eq!((err.line, err.column), (2, 6));
+
+    let err = assemble(&lines("jmp 1 2 3")).unwrap_err();
+    assert_eq!(err.message, "unknown opcode \"jmp\"");
+}
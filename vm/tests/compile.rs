@@ -0,0 +1,90 @@
+use std::fs;
+use common::InputSource;
+use vm::{assemble, Compiled, Instruction, Machine, Opcode, Overflow, Program, Word};
+use crate::util::Lcg;
+
+mod util;
+
+/// The programs in the corpus take their input in r4.
+fn corpus() -> Vec<(String, Program)> {
+    let mut paths: Vec<_> = fs::read_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    paths.into_iter().map(|path| {
+        let name = path.display().to_string();
+        let lines = InputSource::File(path).lines("test", "").unwrap();
+        (name, assemble(&lines).unwrap())
+    }).collect()
+}
+
+/// Run the program with both backends, and check they end up the same.
+fn check(name: &str, program: &Program, machine: &Machine) {
+    let mut interpreted = machine.clone();
+    let expected = interpreted.run(program).map_err(|e| e.to_string());
+
+    let mut compiled = machine.clone();
+    let code = Compiled::new(program, machine.registers().len()).unwrap();
+    let result = code.run(&mut compiled).map_err(|e| e.to_string());
+
+    assert_eq!(result, expected, "{}", name);
+    assert_eq!(compiled, interpreted, "{}", name);
+}
+
+#[test]
+fn corpus_programs() {
+    for (name, program) in corpus() {
+        for &input in &[0, 1, 12, 60] {
+            let mut machine = Machine::with_registers(&[0, 0, 0, 0, input, 0], 64);
+            check(&name, &program, &machine);
+
+            // Stopping at the step limit should leave the machine in the
+            // same place, too.
+            machine.set_step_limit(Some(1000));
+            check(&name, &program, &machine);
+        }
+    }
+}
+
+const POLICIES: [Overflow; 4] = [Overflow::Wrapping, Overflow::Checked, Overflow::Saturating, Overflow::Widened];
+
+#[test]
+fn straight_line() {
+    let mut rng = Lcg::new(1);
+    for &width in &[8, 32, 64] {
+        for &policy in &POLICIES {
+            for _ in 0 .. 50 {
+                let program = rng.program(4, 300, 100, None);
+                let values: Vec<Word> = (0 .. 4).map(|_| rng.next(1000)).collect();
+                let mut machine = Machine::with_registers(&values, width);
+                machine.set_overflow(policy);
+                check("straight line", &program, &machine);
+            }
+        }
+    }
+}
+
+#[test]
+fn jumping() {
+    let mut rng = Lcg::new(2);
+    for &width in &[8, 32, 64] {
+        for _ in 0 .. 50 {
+            let ip = rng.next(6);
+            let program = rng.program(6, 300, 30, Some(ip));
+            for &policy in &POLICIES {
+                let mut machine = Machine::new(6, width);
+                machine.set_step_limit(Some(10_000));
+                machine.set_overflow(policy);
+                check("jumping", &program, &machine);
+            }
+        }
+    }
+}
+
+#[test]
+fn bad_register() {
+    let program = Program::new(None, vec![Instruction::new(Opcode::Addr, 0, 7, 1)]);
+    assert!(Compiled::new(&program, 4).is_err());
+    assert!(Compiled::new(&program, 8).is_ok());
+}
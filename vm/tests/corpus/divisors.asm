@@ -0,0 +1,18 @@
+; Add up the divisors of r4, the slow way.
+#ip 5
+seti 1 0 r1         ; i = 1
+seti 1 0 r2         ; j = 1
+mulr r1 r2 r3
+eqrr r3 r4 r3       ; if i * j == r4
+addr r3 r5 r5
+addi r5 1 r5
+addr r1 r0 r0       ; then r0 += i
+addi r2 1 r2        ; j += 1
+gtrr r2 r4 r3       ; inner loop until j > r4
+addr r5 r3 r5
+seti 1 0 r5
+addi r1 1 r1        ; i += 1
+gtrr r1 r4 r3       ; outer loop until i > r4
+addr r3 r5 r5
+seti 0 0 r5
+mulr r5 r5 r5       ; halt
@@ -0,0 +1,13 @@
+; Add up 1 to r4, r4 times over.
+#ip 5
+seti 0 0 r1         ; i = 0
+seti 0 0 r2         ; j = 0
+addi r2 1 r2        ; j += 1
+addr r0 r2 r0
+gtrr r2 r4 r3       ; inner loop until j > r4
+addr r3 r5 r5
+seti 1 0 r5
+addi r1 1 r1        ; i += 1
+gtrr r1 r4 r3       ; outer loop until i > r4
+addr r3 r5 r5
+seti 0 0 r5
@@ -0,0 +1,118 @@
+use vm::{Breakpoint, Debugger, Error, Machine, Opcode, Stop};
+use crate::util::count_program;
+
+mod util;
+
+/// The counting program, with r0 set to 3.
+fn debugger() -> Debugger {
+    Debugger::new(count_program(), Machine::with_registers(&[3, 0, 0, 0], 32))
+}
+
+/// Where the debugger is: the next address, the steps run, and r1.
+fn at(debugger: &Debugger) -> (usize, u64, u64) {
+    let machine = debugger.machine();
+    (machine.ip(), machine.steps(), machine.registers()[1])
+}
+
+#[test]
+fn breakpoints() {
+    let mut dbg = debugger();
+    dbg.add_breakpoint(Breakpoint::Ip(2));
+    dbg.add_breakpoint(Breakpoint::Ip(2));
+    assert_eq!(dbg.breakpoints(), &[Breakpoint::Ip(2)]);
+
+    assert_eq!(dbg.cont().unwrap(), Stop::Breakpoint(Breakpoint::Ip(2)));
+    assert_eq!(at(&dbg), (2, 2, 1));
+
+    // Continuing from a breakpoint runs past it, to the next time round.
+    assert_eq!(dbg.cont().unwrap(), Stop::Breakpoint(Breakpoint::Ip(2)));
+    assert_eq!(at(&dbg), (2, 6, 2));
+
+    assert!(dbg.remove_breakpoint(Breakpoint::Ip(2)));
+    assert!(!dbg.remove_breakpoint(Breakpoint::Ip(2)));
+    dbg.add_breakpoint(Breakpoint::Opcode(Opcode::Seti));
+    assert_eq!(dbg.cont().unwrap(), Stop::Breakpoint(Breakpoint::Opcode(Opcode::Seti)));
+    assert_eq!(at(&dbg), (4, 8, 2));
+
+    // The last time round, the jump back is skipped.
+    assert_eq!(dbg.cont().unwrap(), Stop::Halted);
+    assert_eq!(at(&dbg), (5, 12, 3));
+    assert!(dbg.halted());
+    assert_eq!(dbg.step().unwrap(), Stop::Halted);
+    assert_eq!(dbg.cont().unwrap(), Stop::Halted);
+    assert_eq!(dbg.machine().steps(), 12);
+}
+
+#[test]
+fn watchpoints() {
+    let mut dbg = debugger();
+    dbg.watch(2).unwrap();
+    dbg.watch(2).unwrap();
+    assert_eq!(dbg.watches(), &[2]);
+    assert_eq!(dbg.watch(4), Err(Error::BadRegister { register: 4, count: 4 }));
+
+    // r2 only changes once r1 reaches r0.
+    assert_eq!(dbg.cont().unwrap(), Stop::Watch { register: 2, old: 0, new: 1 });
+    assert_eq!(at(&dbg), (3, 11, 3));
+    assert!(dbg.unwatch(2));
+    assert!(!dbg.unwatch(2));
+}
+
+/// The instruction pointer's register changes on almost every step, but
+/// a watch on it only stops for jumps.
+#[test]
+fn watching_the_ip() {
+    let mut dbg = debugger();
+    dbg.watch(3).unwrap();
+
+    // The jump back from 4 to 1.
+    assert_eq!(dbg.cont().unwrap(), Stop::Watch { register: 3, old: 3, new: 0 });
+    assert_eq!(at(&dbg), (1, 5, 1));
+    assert_eq!(dbg.cont().unwrap(), Stop::Watch { register: 3, old: 3, new: 0 });
+    assert_eq!(at(&dbg), (1, 9, 2));
+
+    // The skip past the jump, off the end of the program.
+    assert_eq!(dbg.cont().unwrap(), Stop::Watch { register: 3, old: 2, new: 4 });
+    assert_eq!(at(&dbg), (5, 12, 3));
+    assert!(dbg.halted());
+}
+
+#[test]
+fn back() {
+    let mut dbg = debugger();
+    assert!(!dbg.back());
+
+    let mut states = vec![dbg.machine().clone()];
+    for _ in 0 .. 6 {
+        assert_eq!(dbg.step().unwrap(), Stop::Step);
+        states.push(dbg.machine().clone());
+    }
+    assert_eq!(at(&dbg), (2, 6, 2));
+    assert_eq!(dbg.history_len(), 6);
+
+    // Each step back restores the whole machine, step count included.
+    for expected in states.iter().rev().skip(1) {
+        assert!(dbg.back());
+        assert_eq!(dbg.machine(), expected);
+    }
+    assert_eq!(dbg.history_len(), 0);
+    assert!(!dbg.back());
+    assert_eq!(dbg.machine(), &states[0]);
+
+    // Going back over a breakpoint, and continuing, stops at it again.
+    dbg.add_breakpoint(Breakpoint::Ip(4));
+    assert_eq!(dbg.cont().unwrap(), Stop::Breakpoint(Breakpoint::Ip(4)));
+    let stopped = dbg.machine().clone();
+    assert!(dbg.back());
+    assert_eq!(at(&dbg), (3, 3, 1));
+    assert_eq!(dbg.cont().unwrap(), Stop::Breakpoint(Breakpoint::Ip(4)));
+    assert_eq!(dbg.machine(), &stopped);
+
+    // And so does going back from the end.
+    dbg.remove_breakpoint(Breakpoint::Ip(4));
+    assert_eq!(dbg.cont().unwrap(), Stop::Halted);
+    assert!(dbg.back());
+    assert_eq!(at(&dbg), (3, 11, 3));
+    assert!(!dbg.halted());
+    assert_eq!(dbg.machine().registers(), &[3, 3, 1, 2]);
+}
@@ -0,0 +1,62 @@
+use std::fs;
+use common::InputSource;
+use vm::{assemble, decompile, Exit, Machine, Program, Word};
+use crate::util::Lcg;
+
+mod util;
+
+fn corpus(name: &str) -> Program {
+    let path = format!("{}/tests/corpus/{}", env!("CARGO_MANIFEST_DIR"), name);
+    let text = fs::read_to_string(&path).unwrap();
+    assemble(&InputSource::Text(text).lines("test", name).unwrap()).unwrap()
+}
+
+#[test]
+fn nested_loops() {
+    let decompiled = decompile(&corpus("divisors.asm"));
+
+    // The compare and the jump become a conditional jump.
+    assert_eq!(decompiled.blocks[2].start, 2);
+    match &decompiled.blocks[2].exit {
+        Exit::Branch { cond, taken: 6, not_taken: 5 } => assert_eq!(cond.to_string(), "r1 * r2 == r4"),
+        exit => panic!("unexpected exit {:?}", exit),
+    }
+
+    let headers: Vec<usize> = decompiled.loops.iter().map(|l| decompiled.blocks[l.header].start).collect();
+    assert_eq!(headers, vec![1, 2]);
+    assert!(decompiled.blocks.iter().all(|b| b.reachable));
+
+    let text = decompiled.pseudocode().join("\n");
+    assert!(text.contains("loop {"));
+    assert!(text.contains("r0 = r1 + r0"));
+
+    let dot = decompiled.to_dot();
+    assert!(dot.starts_with("digraph cfg {"));
+    assert!(dot.contains("[color=red]"));
+}
+
+/// Without an instruction pointer binding, running the decompiled
+/// statements should give the same registers as running the program.
+#[test]
+fn straight_line_matches() {
+    let mut rng = Lcg::new(7);
+    for _ in 0 .. 200 {
+        let program = rng.program(4, 10, 20, None);
+        let start: Vec<Word> = (0 .. 4).map(|_| rng.next(10)).collect();
+
+        // Leave out programs that divide by zero.
+        let mut machine = Machine::with_registers(&start, 64);
+        if machine.run(&program).is_err() {
+            continue;
+        }
+
+        let decompiled = decompile(&program);
+        let mut regs = start.clone();
+        for block in &decompiled.blocks {
+            for stmt in &block.stmts {
+                regs[stmt.reg as usize] = stmt.expr.eval(&regs).unwrap();
+            }
+        }
+        assert_eq!(regs, machine.registers(), "{}", decompiled.pseudocode().join("\n"));
+    }
+}
@@ -0,0 +1,148 @@
+use vm::{Error, find_mapping, find_mappings, Instruction, InstructionSet, Machine, MappingError, Opcode, Sample, Word};
+use crate::util::Lcg;
+
+mod util;
+
+const WIDTH: u32 = 32;
+
+/// Numbers the opcodes in the set in reverse, so the map isn't the
+/// identity.
+fn opmap(set: &InstructionSet) -> Vec<Opcode> {
+    set.iter().collect::<Vec<_>>().into_iter().rev().collect()
+}
+
+/// Build a sample by running the instruction, with the opcode numbered
+/// according to `opmap`.  There is no sample for a division by zero.
+fn try_sample(opmap: &[Opcode], code: Word, a: Word, b: Word, c: Word, before: [Word; 4]) -> Option<Sample> {
+    let mut machine = Machine::with_registers(&before, WIDTH);
+    machine.exec(&Instruction::new(opmap[code as usize], a, b, c)).ok()?;
+    Some(Sample {
+        before: before.to_vec(),
+        instr: [code, a, b, c],
+        after: machine.registers().to_vec(),
+        line: None,
+    })
+}
+
+fn sample(opmap: &[Opcode], code: Word, a: Word, b: Word, c: Word, before: [Word; 4]) -> Sample {
+    try_sample(opmap, code, a, b, c, before).unwrap()
+}
+
+/// Plenty of samples for each number, with values from a simple generator.
+fn samples(opmap: &[Opcode]) -> Vec<Sample> {
+    let mut rng = Lcg::new(12345);
+    let mut result = vec![];
+    for _ in 0 .. 20 {
+        for code in 0 .. opmap.len() as Word {
+            let before = [rng.next(16), rng.next(16), rng.next(16), rng.next(16)];
+            result.extend(try_sample(opmap, code, rng.next(4), rng.next(4), rng.next(4), before));
+        }
+    }
+    result
+}
+
+#[test]
+fn unique_mapping() {
+    let set = InstructionSet::device();
+    let opmap = opmap(&set);
+    assert_eq!(find_mapping(&samples(&opmap), &set, WIDTH).unwrap(), opmap);
+}
+
+#[test]
+fn extended_set() {
+    let set = InstructionSet::extended();
+    let opmap = opmap(&set);
+    assert_eq!(opmap.len(), Opcode::COUNT);
+    assert_eq!(find_mapping(&samples(&opmap), &set, WIDTH).unwrap(), opmap);
+
+    // The device's own samples can't tell its opcodes apart from the
+    // extensions they never use.
+    let device = InstructionSet::device();
+    let mut padded = device.ops().to_vec();
+    padded.extend(set.iter().filter(|&op| !device.contains(op)));
+    let samples: Vec<Sample> = samples(&padded).into_iter()
+        .filter(|s| (s.code() as usize) < device.len())
+        .collect();
+    match find_mapping(&samples, &set, WIDTH) {
+        Err(MappingError::Ambiguous(codes)) => {
+            let codes: Vec<Word> = codes.iter().map(|(code, _)| *code).collect();
+            assert_eq!(codes, (device.len() as Word .. set.len() as Word).collect::<Vec<_>>());
+        }
+        other => panic!("expected ambiguity, got {:?}", other),
+    }
+}
+
+#[test]
+fn smaller_set() {
+    let set = InstructionSet::new(vec![Opcode::Seti, Opcode::Addr, Opcode::Mulr, Opcode::Eqrr, Opcode::Bani]).unwrap();
+    let opmap = opmap(&set);
+    let mut samples = samples(&opmap);
+    assert_eq!(find_mapping(&samples, &set, WIDTH).unwrap(), opmap);
+
+    // A number past the end of the set is an error.
+    samples[3].instr[0] = 5;
+    match find_mapping(&samples, &set, WIDTH) {
+        Err(err @ MappingError::Contradiction { .. }) => {
+            assert!(err.to_string().contains("there are only 5 opcodes"), "{}", err);
+        }
+        other => panic!("expected a contradiction, got {:?}", other),
+    }
+}
+
+#[test]
+fn duplicate_opcode() {
+    match InstructionSet::new(vec![Opcode::Seti, Opcode::Addr, Opcode::Divr, Opcode::Addr]) {
+        Err(err) => {
+            assert_eq!(err, Error::DuplicateOpcode(Opcode::Addr));
+            assert_eq!(err.to_string(), "Opcode addr is in the instruction set twice");
+        }
+        other => panic!("expected an error, got {:?}", other),
+    }
+    assert_eq!(InstructionSet::new(vec![]).unwrap().len(), 0);
+    assert_eq!(InstructionSet::new(InstructionSet::extended().ops().to_vec()).unwrap(), InstructionSet::extended());
+}
+
+#[test]
+fn missing_samples_are_ambiguous() {
+    let set = InstructionSet::device();
+    let opmap = opmap(&set);
+    let samples: Vec<Sample> = samples(&opmap).into_iter()
+        .filter(|s| s.code() != 3 && s.code() != 7)
+        .collect();
+
+    let mapping = find_mappings(&samples, &set, WIDTH, 10).unwrap();
+    assert!(mapping.complete);
+    assert_eq!(mapping.solutions.len(), 2);
+    let mut both = vec![opmap[3], opmap[7]];
+    both.sort_by_key(|&op| op as usize);
+    assert_eq!(mapping.ambiguous, vec![(3, both.clone()), (7, both)]);
+
+    match find_mapping(&samples, &set, WIDTH) {
+        Err(MappingError::Ambiguous(codes)) => assert_eq!(codes, mapping.ambiguous),
+        other => panic!("expected ambiguity, got {:?}", other),
+    }
+}
+
+#[test]
+fn contradiction_names_samples() {
+    let set = InstructionSet::device();
+    let opmap = opmap(&set);
+    let mut samples = samples(&opmap);
+
+    // Claim that number 0 does what number 14 does.
+    let mut bad = sample(&opmap, 14, 2, 3, 0, [5, 9, 6, 1]);
+    bad.instr[0] = 0;
+    bad.line = Some(42);
+    samples.insert(0, bad);
+
+    match find_mapping(&samples, &set, WIDTH) {
+        Err(err @ MappingError::Contradiction { .. }) => {
+            if let MappingError::Contradiction { samples, lines, .. } = &err {
+                assert!(samples.contains(&0));
+                assert_eq!(lines[0], Some(42));
+            }
+            assert!(err.to_string().contains("sample 1 (line 42)"));
+        }
+        other => panic!("expected a contradiction, got {:?}", other),
+    }
+}
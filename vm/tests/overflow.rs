@@ -0,0 +1,44 @@
+use vm::{Error, Instruction, Machine, Opcode, Overflow};
+
+/// Run a single instruction on an 8-bit machine with r0 = 200, r1 = 100.
+fn run(policy: Overflow, instr: Instruction) -> (Result<(), Error>, Vec<u64>) {
+    let mut machine = Machine::with_registers(&[200, 100, 0], 8);
+    machine.set_overflow(policy);
+    let result = machine.exec(&instr);
+    (result, machine.registers().to_vec())
+}
+
+#[test]
+fn policies() {
+    let add = Instruction::new(Opcode::Addr, 0, 1, 2);
+    let mul = Instruction::new(Opcode::Muli, 0, 2, 2);
+    let small = Instruction::new(Opcode::Addi, 1, 55, 2);
+
+    assert_eq!(run(Overflow::Wrapping, add), (Ok(()), vec![200, 100, 44]));
+    assert_eq!(run(Overflow::Saturating, add), (Ok(()), vec![200, 100, 255]));
+    assert_eq!(run(Overflow::Widened, add), (Ok(()), vec![200, 100, 300]));
+    assert_eq!(run(Overflow::Widened, mul), (Ok(()), vec![200, 100, 400]));
+
+    let (result, regs) = run(Overflow::Checked, add);
+    assert_eq!(result, Err(Error::Overflow { op: Opcode::Addr, a: 200, b: 100, width: 8 }));
+    assert_eq!(regs, vec![200, 100, 0]);
+
+    // Results that fit are the same whatever the policy.
+    for &policy in &[Overflow::Wrapping, Overflow::Checked, Overflow::Saturating, Overflow::Widened] {
+        assert_eq!(run(policy, small), (Ok(()), vec![200, 100, 155]));
+    }
+}
+
+#[test]
+fn widened_is_checked_at_64_bits() {
+    let mut machine = Machine::with_registers(&[u64::MAX >> 8, 0], 8);
+    machine.set_overflow(Overflow::Widened);
+    machine.set_register(0, u64::MAX).unwrap();
+    assert!(machine.exec(&Instruction::new(Opcode::Addi, 0, 1, 1)).is_err());
+
+    // Going back to a narrower policy keeps just the bits that fit.
+    machine.set_overflow(Overflow::Saturating);
+    assert_eq!(machine.registers(), &[255, 0]);
+    machine.exec(&Instruction::new(Opcode::Addi, 0, 1, 1)).unwrap();
+    assert_eq!(machine.registers(), &[255, 255]);
+}
@@ -0,0 +1,136 @@
+use vm::{assemble, HotLoop, Machine, Profile, Program, TraceEvent, TraceFormat, TraceWriter};
+use crate::util::{count_program, lines};
+
+mod util;
+
+/// Count r2 up to r0, r0 times over.
+const NESTED: &str = "\
+#ip 4
+seti 0 0 r1
+seti 0 0 r2
+addi r2 1 r2
+eqrr r2 r0 r3
+addr r3 r4 r4
+seti 1 0 r4
+addi r1 1 r1
+eqrr r1 r0 r3
+addr r3 r4 r4
+seti 0 0 r4
+";
+
+/// Run the program to the end, with r0 set to the given value.
+fn trace(program: &Program, r0: u64) -> Vec<TraceEvent> {
+    let mut machine = Machine::with_registers(&[r0, 0, 0, 0, 0], 32);
+    let mut events = vec![];
+    while let Some(event) = machine.step_traced(program).unwrap() {
+        events.push(event);
+    }
+    events
+}
+
+fn write(events: &[TraceEvent], format: TraceFormat) -> String {
+    let mut writer = TraceWriter::new(vec![], format);
+    for event in events {
+        writer.write(event).unwrap();
+    }
+    String::from_utf8(writer.finish().unwrap()).unwrap()
+}
+
+fn profiled(program: &Program, r0: u64) -> Profile {
+    let mut profile = Profile::new();
+    for event in trace(program, r0) {
+        profile.record(&event);
+    }
+    profile
+}
+
+#[test]
+fn events() {
+    let program = count_program();
+    let events = trace(&program, 3);
+    assert_eq!(events.iter().map(|e| e.ip).collect::<Vec<_>>(),
+               vec![0, 1, 2, 3, 4, 1, 2, 3, 4, 1, 2, 3]);
+    for (n, event) in events.iter().enumerate() {
+        assert_eq!(event.step, n as u64 + 1);
+        assert_eq!(event.instr, program.code[event.ip]);
+    }
+    let last = events.last().unwrap();
+    assert_eq!((&last.before[..], &last.after[..], last.next_ip), (&[3, 3, 1, 2, 0][..], &[3, 3, 1, 4, 0][..], 5));
+}
+
+#[test]
+fn csv() {
+    let program = assemble(&lines("#ip 3\nseti 0 0 r1\naddi r1 1 r1\n")).unwrap();
+    let text = write(&trace(&program, 3), TraceFormat::Csv);
+    assert_eq!(text, "\
+step,ip,op,a,b,c,r0_before,r1_before,r2_before,r3_before,r4_before,\
+r0_after,r1_after,r2_after,r3_after,r4_after,next_ip,overflow
+1,0,seti,0,0,1,3,0,0,0,0,3,0,0,0,0,1,wrapping
+2,1,addi,1,1,1,3,0,0,0,0,3,1,0,1,0,2,wrapping
+");
+}
+
+#[test]
+fn jsonl() {
+    let program = assemble(&lines("#ip 3\nseti 0 0 r1\naddi r1 1 r1\n")).unwrap();
+    let text = write(&trace(&program, 3), TraceFormat::Jsonl);
+    assert_eq!(text, "\
+{\"step\":1,\"ip\":0,\"instr\":{\"op\":\"seti\",\"a\":0,\"b\":0,\"c\":1},\
+\"before\":[3,0,0,0,0],\"after\":[3,0,0,0,0],\"next_ip\":1,\"overflow\":\"wrapping\"}
+{\"step\":2,\"ip\":1,\"instr\":{\"op\":\"addi\",\"a\":1,\"b\":1,\"c\":1},\
+\"before\":[3,0,0,0,0],\"after\":[3,1,0,1,0],\"next_ip\":2,\"overflow\":\"wrapping\"}
+");
+}
+
+/// An empty trace is written as nothing at all, not even a CSV header.
+#[test]
+fn empty_trace() {
+    assert_eq!(write(&[], TraceFormat::Csv), "");
+    assert_eq!(write(&[], TraceFormat::Jsonl), "");
+}
+
+#[test]
+fn hot_loops() {
+    let program = count_program();
+    let profile = profiled(&program, 3);
+    assert_eq!(profile.total(), 12);
+    assert_eq!(profile.hits, vec![1, 3, 3, 3, 2]);
+    assert_eq!(profile.hot_loops(), vec![HotLoop { start: 1, end: 4, iterations: 2, instructions: 11 }]);
+
+    // The outer loop takes in the inner one, so it comes first.
+    let program = assemble(&lines(NESTED)).unwrap();
+    let profile = profiled(&program, 3);
+    assert_eq!(profile.hot_loops(), vec![
+        HotLoop { start: 1, end: 9, iterations: 2, instructions: 47 },
+        HotLoop { start: 2, end: 5, iterations: 6, instructions: 33 },
+    ]);
+
+    // A program that never jumps back has no loops.
+    let program = assemble(&lines("seti 1 0 r1\naddi r1 1 r1\n")).unwrap();
+    assert_eq!(profiled(&program, 0).hot_loops(), vec![]);
+}
+
+#[test]
+fn report() {
+    let program = count_program();
+    let report = profiled(&program, 3).report(&program);
+    assert_eq!(report, vec![
+        "12 instructions run",
+        "",
+        "Instructions:",
+        "           1   8.3%    0: seti 0 0 r1",
+        "           3  25.0%    1: addi r1 1 r1",
+        "           3  25.0%    2: eqrr r1 r0 r2",
+        "           3  25.0%    3: addr r2 r3 r3",
+        "           2  16.7%    4: seti 0 0 r3",
+        "",
+        "Opcodes:",
+        "           3 addi",
+        "           3 addr",
+        "           3 eqrr",
+        "           3 seti",
+        "",
+        "Hot loops:",
+        "          11    1..=4    2 iterations",
+    ]);
+}
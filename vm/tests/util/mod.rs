@@ -0,0 +1,54 @@
+//! Helpers shared by the tests.  Each test only uses some of them.
+
+#![allow(dead_code)]
+
+use common::{InputSource, Line};
+use vm::{assemble, Instruction, Opcode, Program, Word};
+
+/// Count r1 up to r0, jumping back from 4 to 1 each time round.  With r0
+/// set to 3, this runs for 12 steps: 0, then 1 to 4 twice, then 1 to 3.
+pub const COUNT: &str = "\
+#ip 3
+seti 0 0 r1
+addi r1 1 r1
+eqrr r1 r0 r2
+addr r2 r3 r3
+seti 0 0 r3
+";
+
+/// Split assembly source into lines, as if it was read from a file.
+pub fn lines(text: &str) -> Vec<Line> {
+    InputSource::Text(text.to_string()).lines("test", "test.asm").unwrap()
+}
+
+pub fn count_program() -> Program {
+    assemble(&lines(COUNT)).unwrap()
+}
+
+/// A simple random number generator, so that the tests are the same every
+/// time.
+pub struct Lcg(u64);
+
+impl Lcg {
+    pub fn new(seed: u64) -> Lcg {
+        Lcg(seed)
+    }
+
+    /// A number from 0 up to, but not including, `n`.
+    pub fn next(&mut self, n: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
+
+    /// A program of random instructions, using the given number of
+    /// registers, and immediate values below `values`.
+    pub fn program(&mut self, registers: Word, values: Word, length: usize, ip_register: Option<Word>) -> Program {
+        let code = (0 .. length).map(|_| {
+            let op = Opcode::from_index(self.next(Opcode::COUNT as u64) as usize).unwrap();
+            let a = if op.a_is_register() { self.next(registers) } else { self.next(values) };
+            let b = if op.b_is_register() { self.next(registers) } else { self.next(values) };
+            Instruction::new(op, a, b, self.next(registers))
+        }).collect();
+        Program::new(ip_register, code)
+    }
+}